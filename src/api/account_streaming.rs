@@ -1,7 +1,9 @@
+use std::sync::Arc;
 use std::time::Duration;
 
 use futures_util::{SinkExt, StreamExt};
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::sync::{broadcast, watch};
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 
 use crate::{
@@ -9,10 +11,14 @@ use crate::{
     Result, TastyTrade,
 };
 
-use super::{order::LiveOrderRecord, position::BriefPosition};
+use super::{
+    order::{LiveOrderRecord, OrderId, OrderStatus},
+    position::BriefPosition,
+};
 
 static WEBSOCKET_DEMO_URL: &str = "wss://streamer.cert.tastyworks.com";
 static WEBSOCKET_URL: &str = "wss://streamer.tastyworks.com";
+const ORDER_UPDATES_CAPACITY: usize = 256;
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "kebab-case")]
@@ -37,14 +43,48 @@ pub struct HandlerAction {
     value: Option<Box<dyn erased_serde::Serialize + Send + Sync>>,
 }
 
-#[derive(Deserialize, Debug)]
-#[serde(tag = "type", content = "data")]
+#[derive(Debug)]
 pub enum AccountMessage {
-    Order(LiveOrderRecord),
+    Order(Box<LiveOrderRecord>),
     AccountBalance(Box<Balance>),
     CurrentPosition(Box<BriefPosition>),
     OrderChain,
     ExternalTransaction,
+    /// A message type not known to this version of the library.
+    Unknown(String),
+}
+
+impl<'de> Deserialize<'de> for AccountMessage {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Tagged {
+            #[serde(rename = "type")]
+            message_type: String,
+            #[serde(default)]
+            data: serde_json::Value,
+        }
+
+        fn data<T: DeserializeOwned, E: serde::de::Error>(
+            data: serde_json::Value,
+        ) -> std::result::Result<T, E> {
+            serde_json::from_value(data).map_err(E::custom)
+        }
+
+        let Tagged {
+            message_type,
+            data: value,
+        } = Tagged::deserialize(deserializer)?;
+        Ok(match message_type.as_str() {
+            "Order" => AccountMessage::Order(data(value)?),
+            "AccountBalance" => AccountMessage::AccountBalance(data(value)?),
+            "CurrentPosition" => AccountMessage::CurrentPosition(data(value)?),
+            "OrderChain" => AccountMessage::OrderChain,
+            "ExternalTransaction" => AccountMessage::ExternalTransaction,
+            _ => AccountMessage::Unknown(message_type),
+        })
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -72,12 +112,37 @@ pub enum AccountEvent {
     ErrorMessage(ErrorMessage),
     StatusMessage(StatusMessage),
     AccountMessage(Box<AccountMessage>),
+    /// A text frame that could not be parsed, e.g. an order update whose data
+    /// this version of the library cannot read.
+    #[serde(skip)]
+    Invalid {
+        data: String,
+        error: serde_json::Error,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connected,
+    Disconnected,
+}
+
+/// Publishes [`ConnectionState::Disconnected`] when a connection task ends,
+/// however it ends.
+struct DisconnectOnDrop(Arc<watch::Sender<ConnectionState>>);
+
+impl Drop for DisconnectOnDrop {
+    fn drop(&mut self) {
+        _ = self.0.send(ConnectionState::Disconnected);
+    }
 }
 
 #[derive(Debug)]
 pub struct AccountStreamer {
     pub event_receiver: flume::Receiver<AccountEvent>,
     pub action_sender: flume::Sender<HandlerAction>,
+    connection_state: watch::Receiver<ConnectionState>,
+    order_updates: broadcast::Sender<(OrderId, OrderStatus)>,
 }
 
 impl AccountStreamer {
@@ -96,6 +161,9 @@ impl AccountStreamer {
         };
 
         let (ws_stream, _response) = connect_async(url).await?;
+        let (state_sender, connection_state) = watch::channel(ConnectionState::Connected);
+        let state_sender = Arc::new(state_sender);
+        let (order_updates, _) = broadcast::channel(ORDER_UPDATES_CAPACITY);
         // let hello = ws_stream.try_next().await?;
         // if let Some(msg) = hello {
         //     match serde_json::from_slice(&msg.into_data())? {
@@ -109,17 +177,37 @@ impl AccountStreamer {
 
         let (mut write, mut read) = ws_stream.split();
 
+        let read_state = DisconnectOnDrop(state_sender.clone());
+        let read_order_updates = order_updates.clone();
         tokio::spawn(async move {
-            while let Some(message) = read.next().await {
-                let data = message.unwrap().into_data();
-                //println!("{:?}", String::from_utf8_lossy(&data));
-                let data: AccountEvent = serde_json::from_slice(&data).unwrap();
-                event_sender.send_async(data).await.unwrap();
+            let _read_state = read_state;
+            while let Some(Ok(message)) = read.next().await {
+                let data = match message {
+                    Message::Text(data) => data,
+                    Message::Close(_) => break,
+                    _ => continue,
+                };
+                //println!("{data:?}");
+                // Frames that fail to parse are passed on rather than ending the stream.
+                let event = match serde_json::from_str::<AccountEvent>(&data) {
+                    Ok(event) => event,
+                    Err(error) => AccountEvent::Invalid { data, error },
+                };
+                if let AccountEvent::AccountMessage(message) = &event {
+                    if let AccountMessage::Order(record) = message.as_ref() {
+                        _ = read_order_updates.send((record.id, record.status.clone()));
+                    }
+                }
+                if event_sender.send_async(event).await.is_err() {
+                    break;
+                }
             }
         });
 
         let token_clone = token.clone();
+        let write_state = DisconnectOnDrop(state_sender);
         tokio::spawn(async move {
+            let _write_state = write_state;
             while let Ok(action) = action_receiver.recv_async().await {
                 let message = SubRequest {
                     auth_token: token_clone.clone(),
@@ -133,7 +221,6 @@ impl AccountStreamer {
                 let message = Message::Text(message);
 
                 if write.send(message).await.is_err() {
-                    // TODO: send message informing user of disconnection
                     break;
                 }
             }
@@ -159,9 +246,26 @@ impl AccountStreamer {
         Ok(Self {
             event_receiver,
             action_sender,
+            connection_state,
+            order_updates,
         })
     }
 
+    /// Watches the state of the websocket connection. Once disconnected, the
+    /// streamer does not reconnect.
+    pub fn connection_state(&self) -> watch::Receiver<ConnectionState> {
+        self.connection_state.clone()
+    }
+
+    /// Status changes of orders, alongside the events. Lagging receivers miss updates.
+    pub(crate) fn order_updates(&self) -> broadcast::Receiver<(OrderId, OrderStatus)> {
+        self.order_updates.subscribe()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        *self.connection_state.borrow() == ConnectionState::Connected
+    }
+
     pub async fn subscribe_to_account<'a>(&self, account: &'a Account<'a>) {
         self.send(
             SubRequestAction::Connect,
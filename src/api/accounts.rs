@@ -1,29 +1,51 @@
+use std::collections::HashMap;
 use std::fmt;
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
 
+use futures_util::future::join_all;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
-use crate::api::base::Result;
-use crate::client::TastyTrade;
+use crate::api::base::{Result, TastyError};
+use crate::client::{ClientMode, TastyTrade};
+use crate::journal::{JournalEntry, JournalOperation, JournalResponse, OrderJournal};
+use crate::watchdog::OrderWatchdog;
 
 use super::base::{Items, Paginated};
-use super::order::{DryRunResult, LiveOrderRecord, Order, OrderId, OrderPlacedResult, PriceEffect};
+use super::money::SignedAmount;
+use super::order::{
+    client_order_tag, DryRunResult, InstrumentType, LiveOrderRecord, Order, OrderId,
+    OrderPlacedResult, OrderSide, OrderType, Symbol, TimeInForce, WarningPolicy,
+};
 use super::position::FullPosition;
+use super::risk::RiskLimits;
+use super::strategy::Strategy;
 
 impl TastyTrade {
-    pub async fn accounts(&self) -> Result<Vec<Account>> {
+    pub async fn accounts(&self) -> Result<Vec<Account<'_>>> {
         let resp: Items<AccountInner> = self.get("/customers/me/accounts").await?;
         Ok(resp
             .items
             .into_iter()
-            .map(|inner| Account { inner, tasty: self })
+            .map(|inner| Account {
+                inner,
+                tasty: self,
+                warning_policy: None,
+                risk_limits: None,
+                journal: None,
+                journal_tag: None,
+                journal_error_handler: None,
+                watchdog: None,
+            })
             .collect())
     }
 
     pub async fn account(
         &self,
         account_number: impl Into<AccountNumber>,
-    ) -> Result<Option<Account>> {
+    ) -> Result<Option<Account<'_>>> {
         let account_number = account_number.into();
         let accounts = self.accounts().await?;
         for account in accounts {
@@ -45,7 +67,7 @@ impl<T: AsRef<str>> From<T> for AccountNumber {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct AccountDetails {
     pub account_number: AccountNumber,
@@ -62,16 +84,50 @@ pub struct AccountDetails {
     pub funding_date: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct AccountInner {
     pub account: AccountDetails,
     pub authority_level: String,
 }
 
+/// Receives journal entries that could not be written, with the error.
+pub type JournalErrorHandler = dyn Fn(&JournalEntry, &io::Error) + Send + Sync;
+
 pub struct Account<'t> {
     pub(crate) inner: AccountInner,
-    tasty: &'t TastyTrade,
+    pub(crate) tasty: &'t TastyTrade,
+    warning_policy: Option<WarningPolicy>,
+    risk_limits: Option<RiskLimits>,
+    journal: Option<Arc<OrderJournal>>,
+    journal_tag: Option<String>,
+    journal_error_handler: Option<Arc<JournalErrorHandler>>,
+    watchdog: Option<Arc<OrderWatchdog>>,
+}
+
+/// An account that owns its client, for background tasks. It keeps the
+/// journal but not the policies, limits or watchdog.
+pub(crate) struct OwnedAccount {
+    tasty: TastyTrade,
+    inner: AccountInner,
+    journal: Option<Arc<OrderJournal>>,
+    journal_tag: Option<String>,
+    journal_error_handler: Option<Arc<JournalErrorHandler>>,
+}
+
+impl OwnedAccount {
+    pub(crate) fn account(&self) -> Account<'_> {
+        Account {
+            inner: self.inner.clone(),
+            tasty: &self.tasty,
+            warning_policy: None,
+            risk_limits: None,
+            journal: self.journal.clone(),
+            journal_tag: self.journal_tag.clone(),
+            journal_error_handler: self.journal_error_handler.clone(),
+            watchdog: None,
+        }
+    }
 }
 
 impl<'t> Account<'t> {
@@ -79,6 +135,62 @@ impl<'t> Account<'t> {
         self.inner.account.account_number.clone()
     }
 
+    pub(crate) fn to_owned_account(&self) -> OwnedAccount {
+        OwnedAccount {
+            tasty: self.tasty.clone(),
+            inner: self.inner.clone(),
+            journal: self.journal.clone(),
+            journal_tag: self.journal_tag.clone(),
+            journal_error_handler: self.journal_error_handler.clone(),
+        }
+    }
+
+    /// Makes [`Account::place_order`] dry-run every order first and refuse it on policy violations.
+    pub fn with_warning_policy(mut self, policy: WarningPolicy) -> Self {
+        self.warning_policy = Some(policy);
+        self
+    }
+
+    /// Makes [`Account::place_order`] and [`Account::replace_order`] refuse orders that break `limits`.
+    pub fn with_risk_limits(mut self, limits: RiskLimits) -> Self {
+        self.risk_limits = Some(limits);
+        self
+    }
+
+    /// Tracks orders placed through this account in `watchdog`, so they are
+    /// cancelled if it fires.
+    pub fn with_watchdog(mut self, watchdog: Arc<OrderWatchdog>) -> Self {
+        self.watchdog = Some(watchdog);
+        self
+    }
+
+    /// Records every dry run, submission and cancellation in `journal`: once
+    /// before the request is sent and once with its response.
+    ///
+    /// If the first entry cannot be written the request is not sent and fails
+    /// with [`TastyError::Journal`]. Failures to write the response entry go to
+    /// the handler set with [`Account::with_journal_error_handler`], since the
+    /// API has already acted on the request by then.
+    pub fn with_journal(mut self, journal: Arc<OrderJournal>) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Sets the tag stored with this account's journal entries.
+    pub fn with_journal_tag(mut self, tag: impl Into<String>) -> Self {
+        self.journal_tag = Some(tag.into());
+        self
+    }
+
+    /// Called with each response entry that could not be written to the journal.
+    pub fn with_journal_error_handler(
+        mut self,
+        handler: impl Fn(&JournalEntry, &io::Error) + Send + Sync + 'static,
+    ) -> Self {
+        self.journal_error_handler = Some(Arc::new(handler));
+        self
+    }
+
     pub async fn balance(&self) -> Result<Balance> {
         let resp = self
             .tasty
@@ -115,6 +227,15 @@ impl<'t> Account<'t> {
         Ok(resp)
     }
 
+    pub async fn trading_status(&self) -> Result<TradingStatus> {
+        self.tasty
+            .get(&format!(
+                "/accounts/{}/trading-status",
+                self.inner.account.account_number.0
+            ))
+            .await
+    }
+
     pub async fn positions(&self) -> Result<Vec<FullPosition>> {
         let resp: Items<FullPosition> = self
             .tasty
@@ -137,8 +258,28 @@ impl<'t> Account<'t> {
         Ok(resp.items)
     }
 
+    /// Order history, most recent first, starting from `start_date`.
+    pub async fn orders(
+        &self,
+        start_date: chrono::NaiveDate,
+        page_offset: usize,
+    ) -> Result<Paginated<LiveOrderRecord>> {
+        let resp: Paginated<LiveOrderRecord> = self
+            .tasty
+            .get_with_query(
+                &format!("/accounts/{}/orders", self.inner.account.account_number.0),
+                &[
+                    ("start-date", &start_date.format("%Y-%m-%d").to_string()),
+                    ("page-offset", &page_offset.to_string()),
+                ],
+            )
+            .await?;
+        Ok(resp)
+    }
+
     pub async fn dry_run(&self, order: &Order) -> Result<DryRunResult> {
-        let resp: DryRunResult = self
+        let requested_at = self.record_request(JournalOperation::DryRun, Some(order), None)?;
+        let resp = self
             .tasty
             .post(
                 &format!(
@@ -147,32 +288,481 @@ impl<'t> Account<'t> {
                 ),
                 order,
             )
-            .await?;
-        Ok(resp)
+            .await;
+        self.record_response(
+            JournalOperation::DryRun,
+            Some(order),
+            None,
+            requested_at,
+            &resp,
+            JournalResponse::DryRun,
+        );
+        resp
     }
 
+    /// In [`ClientMode::DryRunOnly`] the order is only dry-run and a simulated result returned.
     pub async fn place_order(&self, order: &Order) -> Result<OrderPlacedResult> {
-        let resp: OrderPlacedResult = self
+        match self.tasty.mode() {
+            ClientMode::ReadOnly => return Err(TastyError::Forbidden(ClientMode::ReadOnly)),
+            ClientMode::DryRunOnly => {
+                return Ok(self.checked_dry_run(order).await?.into_simulated(order));
+            }
+            ClientMode::Full => {}
+        }
+        if self.warning_policy.is_some() || self.risk_limits.is_some() {
+            self.checked_dry_run(order).await?;
+        }
+
+        let requested_at = self.record_request(JournalOperation::PlaceOrder, Some(order), None)?;
+        let resp = self
             .tasty
             .post(
                 &format!("/accounts/{}/orders", self.inner.account.account_number.0),
                 order,
             )
-            .await?;
-        Ok(resp)
+            .await;
+        self.record_response(
+            JournalOperation::PlaceOrder,
+            Some(order),
+            None,
+            requested_at,
+            &resp,
+            JournalResponse::OrderPlaced,
+        );
+        if let (Some(watchdog), Ok(placed)) = (&self.watchdog, &resp) {
+            watchdog.track(placed.order.id);
+        }
+        resp
+    }
+
+    /// Replaces a live order with `order`.
+    ///
+    /// In [`ClientMode::DryRunOnly`] the new order is only dry-run and a
+    /// simulated record carrying `id` is returned.
+    pub async fn replace_order(&self, id: OrderId, order: &Order) -> Result<LiveOrderRecord> {
+        match self.tasty.mode() {
+            ClientMode::ReadOnly => return Err(TastyError::Forbidden(ClientMode::ReadOnly)),
+            ClientMode::DryRunOnly => {
+                let dry_run = self.checked_dry_run(order).await?;
+                let mut record = dry_run.into_simulated(order).order;
+                record.id = id;
+                return Ok(record);
+            }
+            ClientMode::Full => {}
+        }
+        if self.warning_policy.is_some() || self.risk_limits.is_some() {
+            self.checked_dry_run(order).await?;
+        }
+
+        let requested_at =
+            self.record_request(JournalOperation::ReplaceOrder, Some(order), Some(id))?;
+        let resp = self
+            .tasty
+            .put(
+                &format!(
+                    "/accounts/{}/orders/{}",
+                    self.inner.account.account_number.0, id.0
+                ),
+                order,
+            )
+            .await;
+        self.record_response(
+            JournalOperation::ReplaceOrder,
+            Some(order),
+            Some(id),
+            requested_at,
+            &resp,
+            JournalResponse::OrderReplaced,
+        );
+        if let (Some(watchdog), Ok(record)) = (&self.watchdog, &resp) {
+            watchdog.untrack(id);
+            watchdog.track(record.id);
+        }
+        resp
     }
 
     pub async fn cancel_order(&self, id: OrderId) -> Result<LiveOrderRecord> {
-        self.tasty
+        if self.tasty.mode() != ClientMode::Full {
+            return Err(TastyError::Forbidden(self.tasty.mode()));
+        }
+        let requested_at = self.record_request(JournalOperation::CancelOrder, None, Some(id))?;
+        let resp = self
+            .tasty
             .delete(&format!(
                 "/accounts/{}/orders/{}",
                 self.inner.account.account_number.0, id.0
             ))
-            .await
+            .await;
+        self.record_response(
+            JournalOperation::CancelOrder,
+            None,
+            Some(id),
+            requested_at,
+            &resp,
+            JournalResponse::OrderCancelled,
+        );
+        if let (Some(watchdog), Ok(_)) = (&self.watchdog, &resp) {
+            watchdog.untrack(id);
+        }
+        resp
+    }
+
+    /// Places an order without risking a duplicate when the outcome of a submission is unknown.
+    ///
+    /// Untagged orders are given a [`client_order_tag`]. When a submission fails
+    /// ambiguously (see [`TastyError::is_ambiguous`]), live orders and today's order
+    /// history are searched for the tagged order before trying again, up to
+    /// `max_attempts` submissions in total.
+    pub async fn place_order_guarded(
+        &self,
+        order: &Order,
+        max_attempts: usize,
+    ) -> Result<GuardedSubmission> {
+        let order = match order.external_identifier() {
+            Some(_) => order.clone(),
+            None => order.clone().with_external_identifier(client_order_tag()),
+        };
+        // Allow for clock skew between us and the API when comparing receive times.
+        let since = chrono::Utc::now() - chrono::Duration::minutes(1);
+
+        let mut attempt = 1;
+        loop {
+            let error = match self.place_order(&order).await {
+                Ok(placed) => return Ok(GuardedSubmission::Placed(placed)),
+                Err(e) if e.is_ambiguous() => e,
+                Err(e) => return Err(e),
+            };
+            tokio::time::sleep(GUARDED_SUBMIT_SETTLE).await;
+            if let Some(existing) = self.find_submitted(&order, since).await? {
+                if let Some(watchdog) = &self.watchdog {
+                    watchdog.track(existing.id);
+                }
+                return Ok(GuardedSubmission::Existing(existing));
+            }
+            if attempt >= max_attempts {
+                return Err(error);
+            }
+            attempt += 1;
+        }
+    }
+
+    async fn find_submitted(
+        &self,
+        order: &Order,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<LiveOrderRecord>> {
+        let is_submitted = |record: &LiveOrderRecord| {
+            record.matches(order) && record.received_at.is_none_or(|at| at >= since)
+        };
+        if let Some(record) = self.live_orders().await?.into_iter().find(is_submitted) {
+            return Ok(Some(record));
+        }
+        // The history is most recent first, so stop at the first page that
+        // reaches back before `since`.
+        let mut page_offset = 0;
+        loop {
+            let page = self.orders(since.date_naive(), page_offset).await?;
+            let reached_since = page.items.is_empty()
+                || page
+                    .items
+                    .iter()
+                    .any(|record| record.received_at.is_some_and(|at| at < since));
+            if let Some(record) = page.items.into_iter().find(is_submitted) {
+                return Ok(Some(record));
+            }
+            page_offset += 1;
+            if reached_since || page_offset >= page.pagination.total_pages {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Number of orders placed today (UTC), in any state, counted across all
+    /// pages of the order history.
+    async fn orders_placed_today(&self) -> Result<usize> {
+        let today = chrono::Utc::now().date_naive();
+        let mut count = 0;
+        let mut page_offset = 0;
+        loop {
+            let page = self.orders(today, page_offset).await?;
+            count += page
+                .items
+                .iter()
+                .filter(|record| record.received_at.is_none_or(|at| at.date_naive() == today))
+                .count();
+            page_offset += 1;
+            if page.items.is_empty() || page_offset >= page.pagination.total_pages {
+                return Ok(count);
+            }
+        }
+    }
+
+    /// Dry-runs `order` and checks the result against the warning policy and risk limits, if any.
+    async fn checked_dry_run(&self, order: &Order) -> Result<DryRunResult> {
+        let dry_run = self.dry_run(order).await?;
+        if let Some(policy) = &self.warning_policy {
+            let violations = policy.check(&dry_run);
+            if !violations.is_empty() {
+                return Err(TastyError::Preflight(violations));
+            }
+        }
+        if let Some(limits) = &self.risk_limits {
+            let positions = if limits.needs_positions() {
+                self.positions().await?
+            } else {
+                vec![]
+            };
+            let orders_today = if limits.max_daily_orders.is_some() {
+                self.orders_placed_today().await?
+            } else {
+                0
+            };
+            let violations = limits.check(order, &dry_run, &positions, orders_today);
+            if !violations.is_empty() {
+                return Err(TastyError::RiskLimits(violations));
+            }
+        }
+        Ok(dry_run)
+    }
+
+    /// Journals a request about to be sent, if a journal is attached, and
+    /// returns the request time. The request must not be sent if this fails.
+    fn record_request(
+        &self,
+        operation: JournalOperation,
+        order: Option<&Order>,
+        order_id: Option<OrderId>,
+    ) -> io::Result<chrono::DateTime<chrono::Utc>> {
+        let requested_at = chrono::Utc::now();
+        if let Some(journal) = &self.journal {
+            journal.append(&self.journal_entry(
+                operation,
+                order,
+                order_id,
+                requested_at,
+                JournalResponse::Pending,
+            ))?;
+        }
+        Ok(requested_at)
+    }
+
+    /// Journals the response to a request, if a journal is attached.
+    ///
+    /// The API has already acted on the request, so a write failure is passed
+    /// to the journal error handler rather than failing the call.
+    fn record_response<T: Clone>(
+        &self,
+        operation: JournalOperation,
+        order: Option<&Order>,
+        order_id: Option<OrderId>,
+        requested_at: chrono::DateTime<chrono::Utc>,
+        resp: &Result<T>,
+        ok: fn(T) -> JournalResponse,
+    ) {
+        let Some(journal) = &self.journal else {
+            return;
+        };
+        let response = match resp {
+            Ok(value) => ok(value.clone()),
+            Err(e) => e.into(),
+        };
+        let mut entry = self.journal_entry(operation, order, order_id, requested_at, response);
+        entry.responded_at = Some(chrono::Utc::now());
+        if let Err(e) = journal.append(&entry) {
+            if let Some(handler) = &self.journal_error_handler {
+                handler(&entry, &e);
+            }
+        }
+    }
+
+    fn journal_entry(
+        &self,
+        operation: JournalOperation,
+        order: Option<&Order>,
+        order_id: Option<OrderId>,
+        requested_at: chrono::DateTime<chrono::Utc>,
+        response: JournalResponse,
+    ) -> JournalEntry {
+        JournalEntry {
+            requested_at,
+            responded_at: None,
+            account_number: self.number(),
+            tag: self.journal_tag.clone(),
+            operation,
+            request: order.and_then(|order| serde_json::to_value(order).ok()),
+            order_id,
+            response,
+        }
+    }
+
+    /// Cancels all cancellable live orders matching `filter` concurrently.
+    ///
+    /// The outer result fails only if live orders could not be fetched; each
+    /// cancellation reports its own result.
+    pub async fn cancel_all(
+        &self,
+        filter: &OrderFilter,
+    ) -> Result<Vec<(OrderId, Result<LiveOrderRecord>)>> {
+        let ids: Vec<OrderId> = self
+            .live_orders()
+            .await?
+            .into_iter()
+            .filter(|order| order.cancellable && filter.matches_order(order))
+            .map(|order| order.id)
+            .collect();
+        let results = join_all(ids.iter().map(|id| self.cancel_order(*id))).await;
+        Ok(ids.into_iter().zip(results).collect())
+    }
+
+    /// Cancels matching orders, then submits one closing order per matching position.
+    ///
+    /// For positions, `filter.side` selects the side of the closing order, so
+    /// `OrderSide::Sell` flattens only long positions.
+    pub async fn flatten(&self, filter: &OrderFilter, mode: FlattenMode) -> Result<FlattenReport> {
+        let cancelled = self.cancel_all(filter).await?;
+
+        let mut orders = vec![];
+        let mut unpriced = vec![];
+        for position in self.positions().await? {
+            if !filter.matches_position(&position) {
+                continue;
+            }
+            let closing_side = position.closing_action().and_then(|action| action.side());
+            if filter
+                .side
+                .is_some_and(|side| closing_side.is_some_and(|closing| closing != side))
+            {
+                continue;
+            }
+            match mode.closing_order(&position) {
+                Some(order) => orders.push(order),
+                None if position.closing_action().is_some() => unpriced.push(position.symbol),
+                None => {}
+            }
+        }
+        let placed = join_all(orders.iter().map(|order| self.place_order(order))).await;
+
+        Ok(FlattenReport {
+            cancelled,
+            placed,
+            unpriced,
+        })
     }
 }
 
-#[derive(Debug, Deserialize)]
+/// How long [`Account::place_order_guarded`] waits after an ambiguous failure
+/// before looking for the order, giving an in-flight submission time to land.
+const GUARDED_SUBMIT_SETTLE: Duration = Duration::from_secs(1);
+
+/// Outcome of [`Account::place_order_guarded`].
+#[derive(Debug)]
+pub enum GuardedSubmission {
+    Placed(OrderPlacedResult),
+    /// An earlier, ambiguously failed attempt had reached the exchange.
+    Existing(LiveOrderRecord),
+}
+
+/// Selects orders and positions for bulk operations. Unset fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct OrderFilter {
+    pub underlying_symbol: Option<Symbol>,
+    pub instrument_type: Option<InstrumentType>,
+    pub side: Option<OrderSide>,
+}
+
+impl OrderFilter {
+    pub fn matches_order(&self, order: &LiveOrderRecord) -> bool {
+        self.underlying_symbol
+            .as_ref()
+            .is_none_or(|symbol| &order.underlying_symbol == symbol)
+            && self
+                .instrument_type
+                .as_ref()
+                .is_none_or(|ty| order.legs.iter().any(|leg| &leg.instrument_type == ty))
+            && self.side.is_none_or(|side| order.has_side(side))
+    }
+
+    pub fn matches_position(&self, position: &FullPosition) -> bool {
+        self.underlying_symbol
+            .as_ref()
+            .is_none_or(|symbol| &position.underlying_symbol == symbol)
+            && self
+                .instrument_type
+                .as_ref()
+                .is_none_or(|ty| &position.instrument_type == ty)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum FlattenMode {
+    Market,
+    /// Marketable limit orders priced from current quotes supplied by the
+    /// caller, keyed by position symbol: the bid for longs and the ask for
+    /// shorts. Positions without a price are left open and reported in
+    /// [`FlattenReport::unpriced`].
+    MarketableLimit(HashMap<Symbol, Decimal>),
+}
+
+impl FlattenMode {
+    fn closing_order(&self, position: &FullPosition) -> Option<Order> {
+        let strategy = Strategy::close_position(position)?;
+        let order = match self {
+            FlattenMode::Market => Order {
+                time_in_force: TimeInForce::Day,
+                order_type: OrderType::Market,
+                price: None,
+                price_effect: None,
+                stop_trigger: None,
+                value: None,
+                value_effect: None,
+                external_identifier: None,
+                legs: strategy.legs,
+            },
+            FlattenMode::MarketableLimit(prices) => strategy.into_order(
+                OrderType::MarketableLimit,
+                TimeInForce::Day,
+                *prices.get(&position.symbol)?,
+            ),
+        };
+        Some(order)
+    }
+}
+
+#[derive(Debug)]
+pub struct FlattenReport {
+    pub cancelled: Vec<(OrderId, Result<LiveOrderRecord>)>,
+    pub placed: Vec<Result<OrderPlacedResult>>,
+    /// Positions left open because [`FlattenMode::MarketableLimit`] had no price for them.
+    pub unpriced: Vec<Symbol>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct TradingStatus {
+    pub account_number: AccountNumber,
+    pub is_closed: bool,
+    pub is_frozen: bool,
+    pub is_closing_only: bool,
+    #[serde(default)]
+    pub is_futures_closing_only: bool,
+    #[serde(default)]
+    pub is_cryptocurrency_closing_only: bool,
+    /// `None` when the API leaves it out, which is not taken as disabled.
+    #[serde(default)]
+    pub is_futures_enabled: Option<bool>,
+    #[serde(default)]
+    pub is_cryptocurrency_enabled: Option<bool>,
+    pub is_in_margin_call: bool,
+    pub is_pattern_day_trader: bool,
+    pub options_level: String,
+    #[serde(default)]
+    pub short_calls_enabled: Option<bool>,
+    pub updated_at: String,
+}
+
+amount_pair!(pending_cash, "pending-cash", "pending-cash-effect");
+
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Balance {
     pub account_number: AccountNumber,
@@ -226,9 +816,8 @@ pub struct Balance {
     pub cash_available_to_withdraw: Decimal,
     #[serde(with = "rust_decimal::serde::arbitrary_precision")]
     pub day_trade_excess: Decimal,
-    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
-    pub pending_cash: Decimal,
-    pub pending_cash_effect: PriceEffect,
+    #[serde(flatten, with = "pending_cash")]
+    pub pending_cash: SignedAmount,
     #[serde(with = "rust_decimal::serde::arbitrary_precision")]
     pub pending_margin_interest: Decimal,
     #[serde(with = "rust_decimal::serde::arbitrary_precision")]
@@ -236,7 +825,7 @@ pub struct Balance {
     pub updated_at: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct BalanceSnapshot {
     pub account_number: AccountNumber,
@@ -290,9 +879,8 @@ pub struct BalanceSnapshot {
     pub cash_available_to_withdraw: Decimal,
     #[serde(with = "rust_decimal::serde::arbitrary_precision")]
     pub day_trade_excess: Decimal,
-    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
-    pub pending_cash: Decimal,
-    pub pending_cash_effect: PriceEffect,
+    #[serde(flatten, with = "pending_cash")]
+    pub pending_cash: SignedAmount,
     pub snapshot_date: chrono::NaiveDate,
 }
 
@@ -1,7 +1,7 @@
 use std::fmt::Display;
 
 use serde::de::DeserializeOwned;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use serde_with::VecSkipError;
 
@@ -46,14 +46,14 @@ pub struct Paginated<T> {
     pub pagination: Pagination,
 }
 
-#[derive(thiserror::Error, Debug, Deserialize)]
+#[derive(thiserror::Error, Debug, Serialize, Deserialize, Clone)]
 pub struct ApiError {
     pub code: Option<String>,
     pub message: String,
     pub errors: Option<Vec<InnerApiError>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct InnerApiError {
     pub code: Option<String>,
     pub message: String,
@@ -77,6 +77,32 @@ pub enum TastyError {
     DxFeed(#[from] crate::quote_streamer::DxFeedError),
     #[error("Websocket Error")]
     Websocket(#[from] tokio_tungstenite::tungstenite::Error),
+    #[error("Operation not permitted in {0:?} mode")]
+    Forbidden(crate::client::ClientMode),
+    #[error("Order rejected by preflight policy")]
+    Preflight(Vec<crate::api::order::PreflightViolation>),
+    #[error("Order rejected by risk limits")]
+    RiskLimits(Vec<crate::api::risk::RiskViolation>),
+    #[error("Instrument type {0} is not supported")]
+    UnsupportedInstrument(crate::api::order::InstrumentType),
+    #[error("Order journal error")]
+    Journal(#[from] std::io::Error),
+}
+
+impl TastyError {
+    /// Whether a failed request may nonetheless have been carried out by the server,
+    /// e.g. a timeout after the request was sent or an unreadable response.
+    pub fn is_ambiguous(&self) -> bool {
+        match self {
+            TastyError::Reqwest(e) => {
+                !e.is_connect()
+                    && !e.is_builder()
+                    && (e.is_timeout() || e.is_request() || e.is_body() || e.is_decode())
+            }
+            TastyError::Json(_) => true,
+            _ => false,
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, TastyError>;
@@ -1,22 +1,300 @@
-use serde::Deserialize;
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 
+use crate::client::path_segment;
 use crate::Result;
 use crate::TastyTrade;
 
+use super::base::{Items, Paginated};
 use super::order::AsSymbol;
 use super::order::Symbol;
 use super::quote_streaming::DxFeedSymbol;
 
 impl TastyTrade {
     pub async fn get_equity_info(&self, symbol: impl AsSymbol) -> Result<EquityInstrumentInfo> {
-        self.get(format!("/instruments/equities/{}", symbol.as_symbol().0))
-            .await
+        let symbol = symbol.as_symbol();
+        if let Some(info) = self
+            .instrument_cache()
+            .and_then(|cache| cache.equity_info(&symbol))
+        {
+            return Ok(info);
+        }
+        let info: EquityInstrumentInfo = self
+            .get(format!("/instruments/equities/{}", path_segment(&symbol.0)))
+            .await?;
+        if let Some(cache) = self.instrument_cache() {
+            cache.insert_equity_info(info.clone());
+        }
+        Ok(info)
     }
+
+    /// Looks up many equities at once, in as few requests as the URL length allows.
+    pub async fn get_equity_infos<S: AsSymbol>(
+        &self,
+        symbols: &[S],
+    ) -> Result<Vec<EquityInstrumentInfo>> {
+        self.get_batched("/instruments/equities", symbols).await
+    }
+
+    /// All actively traded equities, a page at a time.
+    pub async fn list_active_equities(
+        &self,
+        page_offset: usize,
+    ) -> Result<Paginated<EquityInstrumentInfo>> {
+        self.get_with_query(
+            "/instruments/equities/active",
+            &[("page-offset", &page_offset.to_string())],
+        )
+        .await
+    }
+
+    /// Looks up a future such as `/ESZ3`. The leading slash is optional.
+    pub async fn get_future(&self, symbol: impl AsSymbol) -> Result<FutureInstrument> {
+        self.get(format!(
+            "/instruments/futures/{}",
+            path_segment(symbol.as_symbol().0.trim_start_matches('/'))
+        ))
+        .await
+    }
+
+    /// Looks up many futures at once, in as few requests as the URL length allows.
+    pub async fn get_futures<S: AsSymbol>(&self, symbols: &[S]) -> Result<Vec<FutureInstrument>> {
+        self.get_batched("/instruments/futures", symbols).await
+    }
+
+    /// Lists futures, optionally only those of the given product codes such as `ES`.
+    pub async fn list_futures(&self, product_codes: &[&str]) -> Result<Vec<FutureInstrument>> {
+        let query: Vec<_> = product_codes
+            .iter()
+            .map(|code| ("product-code[]", *code))
+            .collect();
+        let resp: Items<FutureInstrument> =
+            self.get_with_query("/instruments/futures", &query).await?;
+        Ok(resp.items)
+    }
+
+    /// Looks up a cryptocurrency pair such as `BTC/USD`.
+    pub async fn get_cryptocurrency(
+        &self,
+        symbol: impl AsSymbol,
+    ) -> Result<CryptocurrencyInstrument> {
+        self.get(format!(
+            "/instruments/cryptocurrencies/{}",
+            path_segment(&symbol.as_symbol().0)
+        ))
+        .await
+    }
+
+    /// Lists the given cryptocurrencies, or all of them when `symbols` is empty.
+    pub async fn list_cryptocurrencies(
+        &self,
+        symbols: &[&str],
+    ) -> Result<Vec<CryptocurrencyInstrument>> {
+        let query: Vec<_> = symbols.iter().map(|symbol| ("symbol[]", *symbol)).collect();
+        let resp: Items<CryptocurrencyInstrument> = self
+            .get_with_query("/instruments/cryptocurrencies", &query)
+            .await?;
+        Ok(resp.items)
+    }
+}
+
+/// Longest query string sent by the batch lookups, well below common URL length limits.
+const MAX_QUERY_LEN: usize = 4000;
+
+impl TastyTrade {
+    /// Fetches `url` with `symbol[]` set to each symbol, split into as many
+    /// requests as needed to keep the query under [`MAX_QUERY_LEN`].
+    pub(crate) async fn get_batched<T, S>(&self, url: &str, symbols: &[S]) -> Result<Vec<T>>
+    where
+        T: DeserializeOwned,
+        S: AsSymbol,
+    {
+        let mut items = vec![];
+        for chunk in symbol_query_chunks(symbols) {
+            let resp: Items<T> = self.get_with_query(url, &chunk).await?;
+            items.extend(resp.items);
+        }
+        Ok(items)
+    }
+}
+
+fn symbol_query_chunks<S: AsSymbol>(symbols: &[S]) -> Vec<Vec<(&'static str, String)>> {
+    const KEY: &str = "symbol[]";
+    let mut chunks = vec![];
+    let mut chunk = vec![];
+    let mut len = 0;
+    for symbol in symbols {
+        let symbol = symbol.as_symbol().0;
+        // Worst case every character is percent-encoded, plus `&` and `=`.
+        let pair_len = 3 * (KEY.len() + symbol.len()) + 2;
+        if !chunk.is_empty() && len + pair_len > MAX_QUERY_LEN {
+            chunks.push(std::mem::take(&mut chunk));
+            len = 0;
+        }
+        len += pair_len;
+        chunk.push((KEY, symbol));
+    }
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+    chunks
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub struct EquityInstrumentInfo {
+    #[serde(default)]
+    pub id: u64,
+    pub symbol: Symbol,
+    #[serde(default)]
+    pub cusip: Option<String>,
+    #[serde(default)]
+    pub short_description: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub listed_market: Option<String>,
+    #[serde(default)]
+    pub is_index: bool,
+    #[serde(default)]
+    pub is_etf: bool,
+    #[serde(default)]
+    pub lendability: Option<Lendability>,
+    /// Annual borrow rate for short positions, in percent.
+    #[serde(default, with = "rust_decimal::serde::arbitrary_precision_option")]
+    pub borrow_rate: Option<Decimal>,
+    #[serde(default)]
+    pub market_time_instrument_collection: Option<String>,
+    #[serde(default)]
+    pub is_closing_only: bool,
+    #[serde(default)]
+    pub is_options_closing_only: bool,
+    #[serde(default)]
+    pub active: bool,
+    #[serde(default)]
+    pub is_fractional_quantity_eligible: bool,
+    #[serde(default)]
+    pub is_illiquid: bool,
+    #[serde(default)]
+    pub halted_at: Option<String>,
+    #[serde(default)]
+    pub stops_trading_at: Option<String>,
+    pub streamer_symbol: DxFeedSymbol,
+    #[serde(default)]
+    pub tick_sizes: Vec<TickSize>,
+    #[serde(default)]
+    pub option_tick_sizes: Vec<TickSize>,
+}
+
+impl EquityInstrumentInfo {
+    /// Whether the stock can be sold short, i.e. shares can be borrowed
+    /// without arranging a pre-borrow.
+    pub fn is_short_sellable(&self) -> bool {
+        matches!(
+            self.lendability,
+            Some(Lendability::EasyToBorrow | Lendability::LocateRequired)
+        )
+    }
+}
+
+api_enum! {
+    pub enum Lendability {
+        EasyToBorrow => "Easy To Borrow",
+        LocateRequired => "Locate Required",
+        Preborrow => "Preborrow",
+    }
+}
+
+/// Minimum price increment for prices below `threshold`, or for all prices when it is unset.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct TickSize {
+    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
+    pub value: Decimal,
+    #[serde(default, with = "rust_decimal::serde::arbitrary_precision_option")]
+    pub threshold: Option<Decimal>,
+}
+
+impl TickSize {
+    /// The tick that applies to `price` in a table of tick sizes, ordered by threshold.
+    pub fn for_price(ticks: &[TickSize], price: Decimal) -> Option<Decimal> {
+        ticks
+            .iter()
+            .find(|tick| {
+                tick.threshold
+                    .is_none_or(|threshold| price.abs() < threshold)
+            })
+            .map(|tick| tick.value)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct FutureInstrument {
+    pub symbol: Symbol,
+    pub product_code: String,
+    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
+    pub contract_size: Decimal,
+    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
+    pub tick_size: Decimal,
+    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
+    pub notional_multiplier: Decimal,
+    #[serde(default)]
+    pub tick_sizes: Vec<TickSize>,
+    #[serde(default)]
+    pub option_tick_sizes: Vec<TickSize>,
+    pub expiration_date: chrono::NaiveDate,
+    pub last_trade_date: chrono::NaiveDate,
+    #[serde(default)]
+    pub expires_at: Option<String>,
+    pub active: bool,
+    pub active_month: bool,
+    pub next_active_month: bool,
+    pub is_closing_only: bool,
+    #[serde(default)]
+    pub is_tradeable: bool,
+    /// The contract positions should be rolled into, when a roll is due.
+    #[serde(default)]
+    pub roll_target_symbol: Option<Symbol>,
+    #[serde(default)]
+    pub product_group: Option<String>,
+    #[serde(default)]
+    pub exchange: Option<String>,
+    pub streamer_symbol: DxFeedSymbol,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct CryptocurrencyInstrument {
     pub symbol: Symbol,
+    #[serde(default)]
+    pub short_description: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub is_closing_only: bool,
+    pub active: bool,
+    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
+    pub tick_size: Decimal,
+    #[serde(default)]
+    pub tick_sizes: Vec<TickSize>,
+    /// The venues orders are routed to, with their precision limits.
+    #[serde(default)]
+    pub destination_venue_symbols: Vec<DestinationVenueSymbol>,
     pub streamer_symbol: DxFeedSymbol,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct DestinationVenueSymbol {
+    pub symbol: String,
+    pub destination_venue: String,
+    /// Decimal places allowed in order quantities.
+    #[serde(default)]
+    pub max_quantity_precision: Option<u32>,
+    /// Decimal places allowed in order prices.
+    #[serde(default)]
+    pub max_price_precision: Option<u32>,
+    #[serde(default)]
+    pub routable: bool,
+}
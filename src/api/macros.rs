@@ -0,0 +1,109 @@
+/// Declares an enum mirroring a string enum of the tastytrade API.
+///
+/// Each variant is given its wire string. Values not listed deserialize into
+/// `Unknown`, which serializes back to the original string.
+macro_rules! api_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $( $(#[$variant_meta:meta])* $variant:ident => $wire:literal, )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $( $(#[$variant_meta])* $variant, )*
+            /// A value not known to this version of the library.
+            Unknown(String),
+        }
+
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $( Self::$variant => $wire, )*
+                    Self::Unknown(value) => value,
+                }
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = std::convert::Infallible;
+
+            fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+                Ok(match s {
+                    $( $wire => Self::$variant, )*
+                    other => Self::Unknown(other.to_owned()),
+                })
+            }
+        }
+
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(
+                &self,
+                serializer: S,
+            ) -> std::result::Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> std::result::Result<Self, D::Error> {
+                let value = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+                let Ok(parsed) = value.parse();
+                Ok(parsed)
+            }
+        }
+    };
+}
+
+/// Declares a serde `with` module reading a [`SignedAmount`](crate::api::money::SignedAmount)
+/// from an amount field and its effect field, for use with `#[serde(flatten, with = "...")]`.
+///
+/// Append `optional` for `Option<SignedAmount>` fields, which are `None` when the amount is absent.
+macro_rules! amount_pair {
+    ($module:ident, $amount:literal, $effect:literal) => {
+        mod $module {
+            use $crate::api::money::{deserialize_pair, serialize_pair, SignedAmount};
+
+            pub fn serialize<S: serde::Serializer>(
+                value: &SignedAmount,
+                serializer: S,
+            ) -> std::result::Result<S::Ok, S::Error> {
+                serialize_pair(Some(value), &[$amount, $effect], serializer)
+            }
+
+            pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> std::result::Result<SignedAmount, D::Error> {
+                deserialize_pair(deserializer, &[$amount, $effect])?
+                    .ok_or_else(|| serde::de::Error::missing_field($amount))
+            }
+        }
+    };
+    ($module:ident, $amount:literal, $effect:literal, optional) => {
+        mod $module {
+            use $crate::api::money::{deserialize_pair, serialize_pair, SignedAmount};
+
+            pub fn serialize<S: serde::Serializer>(
+                value: &Option<SignedAmount>,
+                serializer: S,
+            ) -> std::result::Result<S::Ok, S::Error> {
+                serialize_pair(value.as_ref(), &[$amount, $effect], serializer)
+            }
+
+            pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> std::result::Result<Option<SignedAmount>, D::Error> {
+                deserialize_pair(deserializer, &[$amount, $effect])
+            }
+        }
+    };
+}
@@ -1,10 +1,20 @@
+#[macro_use]
+mod macros;
+
 pub mod account_streaming;
 pub mod accounts;
 pub mod base;
 pub mod event;
 pub mod instrument;
 pub mod login;
+pub mod money;
 pub mod option_chain;
 pub mod order;
+pub mod order_template;
 pub mod position;
 pub mod quote_streaming;
+pub mod risk;
+pub mod strategy;
+pub mod symbol_search;
+pub mod symbology;
+pub mod validation;
@@ -0,0 +1,311 @@
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use rust_decimal::Decimal;
+use serde::de::{self, IgnoredAny, MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::order::PriceEffect;
+
+/// A monetary amount whose sign carries the price effect: credits are positive, debits negative.
+///
+/// The API reports money as an unsigned amount plus a separate effect field.
+/// Fields of this type are read from and written back to such pairs with the
+/// `amount_pair!` macro, which reads a zero amount with a `None` or missing
+/// effect as zero and rejects any other amount without a `Debit` or `Credit`
+/// effect, naming the effect field.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SignedAmount(pub Decimal);
+
+impl SignedAmount {
+    pub const ZERO: SignedAmount = SignedAmount(Decimal::ZERO);
+
+    /// An amount with a `None` or `Unknown` effect is kept as a credit rather
+    /// than discarded; see [`SignedAmount::try_new`] for the strict version.
+    pub fn new(amount: Decimal, effect: &PriceEffect) -> Self {
+        match effect {
+            PriceEffect::Debit => Self(-amount.abs()),
+            _ => Self(amount.abs()),
+        }
+    }
+
+    /// Signs `amount` by `effect`, or returns `None` if the effect is not
+    /// `Debit` or `Credit` and the amount is not zero.
+    pub fn try_new(amount: Decimal, effect: Option<&PriceEffect>) -> Option<Self> {
+        match effect {
+            Some(effect @ (PriceEffect::Debit | PriceEffect::Credit)) => {
+                Some(Self::new(amount, effect))
+            }
+            _ => amount.is_zero().then_some(Self::ZERO),
+        }
+    }
+
+    pub fn credit(amount: Decimal) -> Self {
+        Self::new(amount, &PriceEffect::Credit)
+    }
+
+    pub fn debit(amount: Decimal) -> Self {
+        Self::new(amount, &PriceEffect::Debit)
+    }
+
+    /// The signed value.
+    pub fn value(&self) -> Decimal {
+        self.0
+    }
+
+    /// The unsigned amount, as reported by the API.
+    pub fn amount(&self) -> Decimal {
+        self.0.abs()
+    }
+
+    pub fn effect(&self) -> PriceEffect {
+        if self.0.is_zero() {
+            PriceEffect::None
+        } else if self.0.is_sign_negative() {
+            PriceEffect::Debit
+        } else {
+            PriceEffect::Credit
+        }
+    }
+
+    pub fn is_debit(&self) -> bool {
+        self.effect() == PriceEffect::Debit
+    }
+
+    pub fn is_credit(&self) -> bool {
+        self.effect() == PriceEffect::Credit
+    }
+}
+
+impl fmt::Display for SignedAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl From<Decimal> for SignedAmount {
+    fn from(value: Decimal) -> Self {
+        Self(value)
+    }
+}
+
+impl From<SignedAmount> for Decimal {
+    fn from(value: SignedAmount) -> Self {
+        value.0
+    }
+}
+
+impl Add for SignedAmount {
+    type Output = SignedAmount;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for SignedAmount {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sub for SignedAmount {
+    type Output = SignedAmount;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl SubAssign for SignedAmount {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Neg for SignedAmount {
+    type Output = SignedAmount;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
+
+impl Mul<Decimal> for SignedAmount {
+    type Output = SignedAmount;
+
+    fn mul(self, rhs: Decimal) -> Self::Output {
+        Self(self.0 * rhs)
+    }
+}
+
+impl Sum for SignedAmount {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a SignedAmount> for SignedAmount {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+/// On its own, a signed amount is written as a plain signed decimal.
+impl Serialize for SignedAmount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Amount(self.0).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SignedAmount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self(Amount::deserialize(deserializer)?.0))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+struct Amount(#[serde(with = "rust_decimal::serde::arbitrary_precision")] Decimal);
+
+#[doc(hidden)]
+pub fn serialize_pair<S: Serializer>(
+    value: Option<&SignedAmount>,
+    fields: &'static [&'static str; 2],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(None)?;
+    if let Some(value) = value {
+        map.serialize_entry(fields[0], &Amount(value.amount()))?;
+        map.serialize_entry(fields[1], &value.effect())?;
+    }
+    map.end()
+}
+
+#[doc(hidden)]
+pub fn deserialize_pair<'de, D: Deserializer<'de>>(
+    deserializer: D,
+    fields: &'static [&'static str; 2],
+) -> Result<Option<SignedAmount>, D::Error> {
+    struct PairVisitor(&'static [&'static str; 2]);
+
+    impl<'de> Visitor<'de> for PairVisitor {
+        type Value = Option<SignedAmount>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "fields `{}` and `{}`", self.0[0], self.0[1])
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut amount: Option<Option<Amount>> = None;
+            let mut effect: Option<Option<PriceEffect>> = None;
+            while let Some(key) = map.next_key::<String>()? {
+                if key == self.0[0] {
+                    amount = Some(map.next_value()?);
+                } else if key == self.0[1] {
+                    effect = Some(map.next_value()?);
+                } else {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+            let Some(amount) = amount.flatten() else {
+                return Ok(None);
+            };
+            let effect = effect.flatten();
+            match SignedAmount::try_new(amount.0, effect.as_ref()) {
+                Some(value) => Ok(Some(value)),
+                None => match effect {
+                    Some(effect) => Err(de::Error::custom(format!(
+                        "invalid `{}` {effect} for non-zero `{}` {}",
+                        self.0[1], self.0[0], amount.0
+                    ))),
+                    None => Err(de::Error::missing_field(self.0[1])),
+                },
+            }
+        }
+    }
+
+    deserializer.deserialize_struct("SignedAmount", fields, PairVisitor(fields))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    amount_pair!(pair, "amount", "amount-effect");
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Pair {
+        #[serde(flatten, with = "pair")]
+        value: SignedAmount,
+    }
+
+    fn parse(value: serde_json::Value) -> serde_json::Result<SignedAmount> {
+        serde_json::from_value::<Pair>(value).map(|pair| pair.value)
+    }
+
+    fn round_trip(value: serde_json::Value) -> SignedAmount {
+        let amount = parse(value.clone()).unwrap();
+        assert_eq!(serde_json::to_value(Pair { value: amount }).unwrap(), value);
+        amount
+    }
+
+    #[test]
+    fn round_trips_debit() {
+        let value = round_trip(json!({ "amount": 12.5, "amount-effect": "Debit" }));
+        assert_eq!(value, SignedAmount::debit(Decimal::new(125, 1)));
+        assert_eq!(value.value(), Decimal::new(-125, 1));
+    }
+
+    #[test]
+    fn round_trips_credit() {
+        let value = round_trip(json!({ "amount": 12.5, "amount-effect": "Credit" }));
+        assert_eq!(value.value(), Decimal::new(125, 1));
+    }
+
+    #[test]
+    fn round_trips_zero_with_none_effect() {
+        let value = round_trip(json!({ "amount": 0, "amount-effect": "None" }));
+        assert_eq!(value, SignedAmount::ZERO);
+    }
+
+    #[test]
+    fn reads_zero_without_effect() {
+        assert_eq!(parse(json!({ "amount": 0 })).unwrap(), SignedAmount::ZERO);
+        assert_eq!(
+            parse(json!({ "amount": 0, "amount-effect": null })).unwrap(),
+            SignedAmount::ZERO
+        );
+    }
+
+    #[test]
+    fn rejects_non_zero_amount_without_sign() {
+        let missing = parse(json!({ "amount": 5 })).unwrap_err();
+        assert!(missing.to_string().contains("amount-effect"));
+        let none = parse(json!({ "amount": 5, "amount-effect": "None" })).unwrap_err();
+        assert!(none.to_string().contains("amount-effect"));
+        let unknown = parse(json!({ "amount": 5, "amount-effect": "Sideways" })).unwrap_err();
+        assert!(unknown.to_string().contains("amount-effect"));
+    }
+
+    #[test]
+    fn new_keeps_amount_without_sign() {
+        let amount = Decimal::from(5);
+        assert_eq!(
+            SignedAmount::new(amount, &PriceEffect::None).value(),
+            amount
+        );
+        assert_eq!(
+            SignedAmount::try_new(amount, Some(&PriceEffect::None)),
+            None
+        );
+        assert_eq!(
+            SignedAmount::try_new(amount, Some(&PriceEffect::Debit)),
+            Some(SignedAmount::debit(amount))
+        );
+    }
+}
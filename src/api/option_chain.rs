@@ -2,15 +2,18 @@ use std::collections::HashMap;
 
 use crate::api::base::Result;
 use rust_decimal::Decimal;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::client::path_segment;
 use crate::TastyTrade;
 
 use super::{
     base::Items,
+    instrument::TickSize,
     order::{AsSymbol, Symbol},
     quote_streaming::DxFeedSymbol,
+    strategy::OptionType,
 };
 
 impl TastyTrade {
@@ -19,30 +22,73 @@ impl TastyTrade {
         symbol: impl Into<Symbol>,
     ) -> Result<NestedOptionChain> {
         let mut resp: Items<NestedOptionChain> = self
-            .get(format!("/option-chains/{}/nested", symbol.into().0))
+            .get(format!(
+                "/option-chains/{}/nested",
+                path_segment(&symbol.into().0)
+            ))
             .await?;
         Ok(resp.items.remove(0))
     }
 
     pub async fn option_chain_for(&self, symbol: impl Into<Symbol>) -> Result<Vec<OptionChain>> {
         let resp: Items<OptionChain> = self
-            .get(format!("/option-chains/{}", symbol.into().0))
+            .get(format!("/option-chains/{}", path_segment(&symbol.into().0)))
             .await?;
         Ok(resp.items)
     }
 
     pub async fn get_option_info(&self, symbol: impl AsSymbol) -> Result<OptionInfo> {
+        let symbol = symbol.as_symbol();
+        if let Some(info) = self
+            .instrument_cache()
+            .and_then(|cache| cache.option_info(&symbol))
+        {
+            return Ok(info);
+        }
+        let info: OptionInfo = self
+            .get(format!(
+                "/instruments/equity-options/{}",
+                path_segment(&symbol.0)
+            ))
+            .await?;
+        if let Some(cache) = self.instrument_cache() {
+            cache.insert_option_info(info.clone());
+        }
+        Ok(info)
+    }
+
+    /// Looks up many equity options at once, in as few requests as the URL length allows.
+    pub async fn get_option_infos<S: AsSymbol>(&self, symbols: &[S]) -> Result<Vec<OptionInfo>> {
+        self.get_batched("/instruments/equity-options", symbols)
+            .await
+    }
+
+    /// The nested option chain of a futures product code such as `ES`.
+    pub async fn nested_future_option_chain_for(
+        &self,
+        product_code: &str,
+    ) -> Result<NestedFutureOptionChain> {
+        self.get(format!(
+            "/futures-option-chains/{}/nested",
+            path_segment(product_code.trim_start_matches('/'))
+        ))
+        .await
+    }
+
+    /// Looks up a future option such as `./ESZ3 EW4Z3 231215C4000`.
+    pub async fn get_future_option(&self, symbol: impl AsSymbol) -> Result<FutureOptionInstrument> {
         self.get(format!(
-            "/instruments/equity-options/{}",
-            symbol.as_symbol().0
+            "/instruments/future-options/{}",
+            path_segment(&symbol.as_symbol().0)
         ))
         .await
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub struct OptionInfo {
+    pub symbol: Symbol,
     pub streamer_symbol: DxFeedSymbol,
 }
 
@@ -66,6 +112,14 @@ pub struct Expiration {
     pub strikes: Vec<Strike>,
 }
 
+impl Expiration {
+    pub fn strike(&self, strike_price: Decimal) -> Option<&Strike> {
+        self.strikes
+            .iter()
+            .find(|strike| strike.strike_price == strike_price)
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Strike {
@@ -84,3 +138,108 @@ pub struct OptionChain {
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
 }
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NestedFutureOptionChain {
+    pub futures: Vec<ChainFuture>,
+    pub option_chains: Vec<FutureOptionChain>,
+}
+
+/// An underlying future listed with a futures option chain.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ChainFuture {
+    pub symbol: Symbol,
+    pub root_symbol: Symbol,
+    pub expiration_date: String,
+    pub days_to_expiration: u64,
+    pub active_month: bool,
+    pub next_active_month: bool,
+    pub stops_trading_at: String,
+    pub expires_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct FutureOptionChain {
+    pub underlying_symbol: Symbol,
+    pub root_symbol: Symbol,
+    /// Exercise style of every expiration in the chain, e.g. `American`.
+    pub exercise_style: String,
+    pub expirations: Vec<FutureOptionExpiration>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct FutureOptionExpiration {
+    /// The future these options deliver, e.g. `/ESZ3`.
+    pub underlying_symbol: Symbol,
+    pub root_symbol: Symbol,
+    pub option_root_symbol: String,
+    pub option_contract_symbol: String,
+    pub asset: String,
+    pub expiration_date: String,
+    pub days_to_expiration: u64,
+    pub expiration_type: String,
+    pub settlement_type: String,
+    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
+    pub notional_value: Decimal,
+    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
+    pub display_factor: Decimal,
+    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
+    pub strike_factor: Decimal,
+    pub stops_trading_at: String,
+    pub expires_at: String,
+    #[serde(default)]
+    pub tick_sizes: Vec<TickSize>,
+    pub strikes: Vec<FutureOptionStrike>,
+}
+
+impl FutureOptionExpiration {
+    pub fn strike(&self, strike_price: Decimal) -> Option<&FutureOptionStrike> {
+        self.strikes
+            .iter()
+            .find(|strike| strike.strike_price == strike_price)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct FutureOptionStrike {
+    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
+    pub strike_price: Decimal,
+    pub call: Symbol,
+    pub put: Symbol,
+    pub call_streamer_symbol: DxFeedSymbol,
+    pub put_streamer_symbol: DxFeedSymbol,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct FutureOptionInstrument {
+    pub symbol: Symbol,
+    pub underlying_symbol: Symbol,
+    pub product_code: String,
+    pub root_symbol: Symbol,
+    pub option_root_symbol: String,
+    pub expiration_date: String,
+    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
+    pub strike_price: Decimal,
+    pub option_type: OptionType,
+    pub exercise_style: String,
+    pub settlement_type: String,
+    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
+    pub multiplier: Decimal,
+    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
+    pub notional_value: Decimal,
+    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
+    pub display_factor: Decimal,
+    pub days_to_expiration: u64,
+    pub is_closing_only: bool,
+    pub active: bool,
+    pub stops_trading_at: String,
+    pub expires_at: String,
+    pub exchange: String,
+    pub streamer_symbol: DxFeedSymbol,
+}
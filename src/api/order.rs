@@ -1,86 +1,128 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use chrono::{DateTime, Utc};
 use derive_builder::Builder;
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, RoundingStrategy};
 use serde::{Deserialize, Serialize};
 
 use crate::accounts::AccountNumber;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub enum PriceEffect {
-    Debit,
-    Credit,
-    None,
+use super::money::SignedAmount;
+
+/// Maximum number of legs tastytrade accepts in a single order.
+pub const MAX_LEGS: usize = 4;
+
+api_enum! {
+    pub enum PriceEffect {
+        Debit => "Debit",
+        Credit => "Credit",
+        None => "None",
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub enum Action {
-    #[serde(rename = "Buy to Open")]
-    BuyToOpen,
-    #[serde(rename = "Sell to Open")]
-    SellToOpen,
-    #[serde(rename = "Buy to Close")]
-    BuyToClose,
-    #[serde(rename = "Sell to Close")]
-    SellToClose,
-    Sell,
+impl PriceEffect {
+    pub fn opposite(&self) -> Self {
+        match self {
+            PriceEffect::Debit => PriceEffect::Credit,
+            PriceEffect::Credit => PriceEffect::Debit,
+            other => other.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum OrderSide {
     Buy,
+    Sell,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub enum InstrumentType {
-    Equity,
-    #[serde(rename = "Equity Option")]
-    EquityOption,
-    #[serde(rename = "Equity Offering")]
-    EquityOffering,
-    Future,
-    #[serde(rename = "Future Option")]
-    FutureOption,
-    Cryptocurrency,
+api_enum! {
+    pub enum Action {
+        BuyToOpen => "Buy to Open",
+        SellToOpen => "Sell to Open",
+        BuyToClose => "Buy to Close",
+        SellToClose => "Sell to Close",
+        Sell => "Sell",
+        Buy => "Buy",
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub enum OrderType {
-    Limit,
-    Market,
-    #[serde(rename = "Marketable Limit")]
-    MarketableLimit,
-    Stop,
-    #[serde(rename = "Stop Limit")]
-    StopLimit,
-    #[serde(rename = "Notional Market")]
-    NotionalMarket,
+impl Action {
+    /// Whether this action buys or sells. `None` for unknown actions.
+    pub fn side(&self) -> Option<OrderSide> {
+        match self {
+            Action::BuyToOpen | Action::BuyToClose | Action::Buy => Some(OrderSide::Buy),
+            Action::SellToOpen | Action::SellToClose | Action::Sell => Some(OrderSide::Sell),
+            Action::Unknown(_) => None,
+        }
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub enum TimeInForce {
-    Day,
-    GTC,
-    GTD,
-    Ext,
-    #[serde(rename = "GTC Ext")]
-    GTCExt,
-    IOC,
+api_enum! {
+    pub enum InstrumentType {
+        Equity => "Equity",
+        EquityOption => "Equity Option",
+        EquityOffering => "Equity Offering",
+        Future => "Future",
+        FutureOption => "Future Option",
+        Cryptocurrency => "Cryptocurrency",
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub enum OrderStatus {
-    Received,
-    Routed,
-    #[serde(rename = "In Flight")]
-    InFlight,
-    Live,
-    #[serde(rename = "Cancel Requested")]
-    CancelRequested,
-    #[serde(rename = "Replace Requested")]
-    ReplaceRequested,
-    Contingent,
-    Filled,
-    Cancelled,
-    Expired,
-    Rejected,
-    Removed,
-    #[serde(rename = "Partially Removed")]
-    PartiallyRemoved,
+api_enum! {
+    pub enum OrderType {
+        Limit => "Limit",
+        Market => "Market",
+        MarketableLimit => "Marketable Limit",
+        Stop => "Stop",
+        StopLimit => "Stop Limit",
+        NotionalMarket => "Notional Market",
+    }
+}
+
+api_enum! {
+    pub enum TimeInForce {
+        Day => "Day",
+        GTC => "GTC",
+        GTD => "GTD",
+        Ext => "Ext",
+        GTCExt => "GTC Ext",
+        IOC => "IOC",
+    }
+}
+
+api_enum! {
+    pub enum OrderStatus {
+        Received => "Received",
+        Routed => "Routed",
+        InFlight => "In Flight",
+        Live => "Live",
+        CancelRequested => "Cancel Requested",
+        ReplaceRequested => "Replace Requested",
+        Contingent => "Contingent",
+        Filled => "Filled",
+        Cancelled => "Cancelled",
+        Expired => "Expired",
+        Rejected => "Rejected",
+        Removed => "Removed",
+        PartiallyRemoved => "Partially Removed",
+    }
+}
+
+impl OrderStatus {
+    /// Whether the order is done and will not change again.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            OrderStatus::Filled
+                | OrderStatus::Cancelled
+                | OrderStatus::Expired
+                | OrderStatus::Rejected
+                | OrderStatus::Removed
+                | OrderStatus::PartiallyRemoved
+        )
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -93,6 +135,12 @@ impl<T: AsRef<str>> From<T> for Symbol {
     }
 }
 
+impl std::fmt::Display for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
 pub trait AsSymbol {
     fn as_symbol(&self) -> Symbol;
 }
@@ -115,64 +163,232 @@ impl AsSymbol for &Symbol {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(transparent)]
 pub struct OrderId(pub u64);
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub struct LiveOrderRecord {
     pub id: OrderId,
     pub account_number: AccountNumber,
     pub time_in_force: TimeInForce,
     pub order_type: OrderType,
-    pub size: u64,
-    pub underlying_symbol: Symbol,
     #[serde(with = "rust_decimal::serde::arbitrary_precision")]
-    pub price: Decimal,
-    pub price_effect: PriceEffect,
+    pub size: Decimal,
+    pub underlying_symbol: Symbol,
+    #[serde(flatten, with = "price")]
+    pub price: Option<SignedAmount>,
+    #[serde(default, with = "rust_decimal::serde::arbitrary_precision_option")]
+    pub stop_trigger: Option<Decimal>,
     pub status: OrderStatus,
     pub cancellable: bool,
     pub editable: bool,
     pub edited: bool,
+    #[serde(default)]
+    pub external_identifier: Option<String>,
+    #[serde(default)]
+    pub received_at: Option<DateTime<Utc>>,
+    pub legs: Vec<LiveOrderLeg>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub struct LiveOrderLeg {
     pub instrument_type: InstrumentType,
     pub symbol: Symbol,
-    pub quantity: u64,
-    pub remaining_quantity: u64,
+    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
+    pub quantity: Decimal,
+    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
+    pub remaining_quantity: Decimal,
     pub action: Action,
-    pub fills: Vec<String>,
+    #[serde(default)]
+    pub fills: Vec<Fill>,
 }
 
-#[derive(Builder, Serialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct Fill {
+    pub fill_id: String,
+    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
+    pub quantity: Decimal,
+    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
+    pub fill_price: Decimal,
+    pub filled_at: String,
+}
+
+impl LiveOrderRecord {
+    /// Whether any leg of this order buys or sells according to `side`.
+    pub fn has_side(&self, side: OrderSide) -> bool {
+        self.legs.iter().any(|leg| leg.action.side() == Some(side))
+    }
+
+    /// Whether this record is the result of submitting `order`.
+    ///
+    /// Tagged orders match on their external identifier alone. Untagged orders
+    /// match when type, time in force, prices and legs are all the same.
+    pub fn matches(&self, order: &Order) -> bool {
+        if let Some(tag) = &order.external_identifier {
+            return self.external_identifier.as_ref() == Some(tag);
+        }
+        let price = order
+            .price
+            .zip(order.price_effect.as_ref())
+            .map(|(price, effect)| SignedAmount::new(price, effect));
+        self.order_type == order.order_type
+            && self.time_in_force == order.time_in_force
+            && self.price == price
+            && self.stop_trigger == order.stop_trigger
+            && self.legs.len() == order.legs.len()
+            && self.legs.iter().zip(&order.legs).all(|(record, leg)| {
+                record.symbol == leg.symbol
+                    && record.action == leg.action
+                    && Some(record.quantity) == leg.quantity
+            })
+    }
+}
+
+/// Generates a tag, unique within this process, for [`Order::with_external_identifier`].
+pub fn client_order_tag() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    format!(
+        "trs-{}-{}-{}",
+        std::process::id(),
+        Utc::now().timestamp_millis(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+#[derive(Builder, Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "kebab-case")]
 #[builder(setter(into))]
 pub struct Order {
-    time_in_force: TimeInForce,
-    order_type: OrderType,
+    pub(crate) time_in_force: TimeInForce,
+    pub(crate) order_type: OrderType,
 
-    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
-    price: Decimal,
-    price_effect: PriceEffect,
-    legs: Vec<OrderLeg>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "rust_decimal::serde::arbitrary_precision_option"
+    )]
+    #[builder(setter(into, strip_option), default)]
+    pub(crate) price: Option<Decimal>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[builder(setter(into, strip_option), default)]
+    pub(crate) price_effect: Option<PriceEffect>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "rust_decimal::serde::arbitrary_precision_option"
+    )]
+    #[builder(setter(into, strip_option), default)]
+    pub(crate) stop_trigger: Option<Decimal>,
+    /// Dollar amount of a [`OrderType::NotionalMarket`] order.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "rust_decimal::serde::arbitrary_precision_option"
+    )]
+    #[builder(setter(into, strip_option), default)]
+    pub(crate) value: Option<Decimal>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[builder(setter(into, strip_option), default)]
+    pub(crate) value_effect: Option<PriceEffect>,
+    /// Client-chosen tag echoed back on the order record.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[builder(setter(into, strip_option), default)]
+    pub(crate) external_identifier: Option<String>,
+    pub(crate) legs: Vec<OrderLeg>,
+}
+
+impl Order {
+    pub fn time_in_force(&self) -> &TimeInForce {
+        &self.time_in_force
+    }
+
+    pub fn order_type(&self) -> &OrderType {
+        &self.order_type
+    }
+
+    pub fn price(&self) -> Option<Decimal> {
+        self.price
+    }
+
+    pub fn price_effect(&self) -> Option<&PriceEffect> {
+        self.price_effect.as_ref()
+    }
+
+    pub fn stop_trigger(&self) -> Option<Decimal> {
+        self.stop_trigger
+    }
+
+    pub fn value(&self) -> Option<Decimal> {
+        self.value
+    }
+
+    pub fn value_effect(&self) -> Option<&PriceEffect> {
+        self.value_effect.as_ref()
+    }
+
+    pub fn external_identifier(&self) -> Option<&str> {
+        self.external_identifier.as_deref()
+    }
+
+    pub fn legs(&self) -> &[OrderLeg] {
+        &self.legs
+    }
+
+    pub fn with_external_identifier(mut self, tag: impl Into<String>) -> Self {
+        self.external_identifier = Some(tag.into());
+        self
+    }
 }
 
 #[derive(Builder, Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "kebab-case")]
 #[builder(setter(into))]
 pub struct OrderLeg {
-    instrument_type: InstrumentType,
-    symbol: Symbol,
-    #[serde(with = "rust_decimal::serde::float")]
-    quantity: Decimal,
-    action: Action,
+    pub(crate) instrument_type: InstrumentType,
+    pub(crate) symbol: Symbol,
+    /// Unset on the legs of [`OrderType::NotionalMarket`] orders, which are sized by value.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "rust_decimal::serde::arbitrary_precision_option"
+    )]
+    #[builder(setter(into, strip_option), default)]
+    pub(crate) quantity: Option<Decimal>,
+    pub(crate) action: Action,
+}
+
+impl OrderLeg {
+    pub fn instrument_type(&self) -> &InstrumentType {
+        &self.instrument_type
+    }
+
+    pub fn symbol(&self) -> &Symbol {
+        &self.symbol
+    }
+
+    pub fn quantity(&self) -> Option<Decimal> {
+        self.quantity
+    }
+
+    pub fn action(&self) -> &Action {
+        &self.action
+    }
+
+    /// Truncates the quantity to the number of decimal places an instrument accepts,
+    /// so that the leg never exceeds the requested size.
+    pub fn with_quantity_precision(mut self, decimal_places: u32) -> Self {
+        self.quantity = self.quantity.map(|quantity| {
+            quantity.round_dp_with_strategy(decimal_places, RoundingStrategy::ToZero)
+        });
+        self
+    }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub struct OrderPlacedResult {
     pub order: LiveOrderRecord,
@@ -181,7 +397,7 @@ pub struct OrderPlacedResult {
     pub fee_calculation: FeeCalculation,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub struct DryRunResult {
     pub order: DryRunRecord,
@@ -190,17 +406,58 @@ pub struct DryRunResult {
     pub fee_calculation: FeeCalculation,
 }
 
-#[derive(Debug, Deserialize)]
+impl DryRunResult {
+    /// What placing `order` would have returned, for callers that only simulate.
+    /// The order id is 0 and the record has no fills.
+    pub fn into_simulated(self, order: &Order) -> OrderPlacedResult {
+        let record = self.order;
+        OrderPlacedResult {
+            order: LiveOrderRecord {
+                id: OrderId(0),
+                account_number: record.account_number,
+                time_in_force: record.time_in_force,
+                order_type: record.order_type,
+                size: record.size,
+                underlying_symbol: record.underlying_symbol,
+                price: record.price,
+                stop_trigger: order.stop_trigger,
+                status: record.status,
+                cancellable: record.cancellable,
+                editable: record.editable,
+                edited: record.edited,
+                external_identifier: order.external_identifier.clone(),
+                received_at: Some(Utc::now()),
+                legs: record
+                    .legs
+                    .into_iter()
+                    .map(|leg| LiveOrderLeg {
+                        instrument_type: leg.instrument_type,
+                        symbol: leg.symbol,
+                        quantity: leg.quantity.unwrap_or_default(),
+                        remaining_quantity: leg.quantity.unwrap_or_default(),
+                        action: leg.action,
+                        fills: vec![],
+                    })
+                    .collect(),
+            },
+            warnings: self.warnings,
+            buying_power_effect: self.buying_power_effect,
+            fee_calculation: self.fee_calculation,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub struct DryRunRecord {
     pub account_number: AccountNumber,
     pub time_in_force: TimeInForce,
     pub order_type: OrderType,
-    pub size: u64,
-    pub underlying_symbol: Symbol,
     #[serde(with = "rust_decimal::serde::arbitrary_precision")]
-    pub price: Decimal,
-    pub price_effect: PriceEffect,
+    pub size: Decimal,
+    pub underlying_symbol: Symbol,
+    #[serde(flatten, with = "price")]
+    pub price: Option<SignedAmount>,
     pub status: OrderStatus,
     pub cancellable: bool,
     pub editable: bool,
@@ -208,31 +465,99 @@ pub struct DryRunRecord {
     pub legs: Vec<OrderLeg>,
 }
 
-#[derive(Debug, Deserialize)]
+amount_pair!(
+    change_in_margin_requirement,
+    "change-in-margin-requirement",
+    "change-in-margin-requirement-effect"
+);
+amount_pair!(
+    change_in_buying_power,
+    "change-in-buying-power",
+    "change-in-buying-power-effect"
+);
+amount_pair!(
+    current_buying_power,
+    "current-buying-power",
+    "current-buying-power-effect"
+);
+amount_pair!(impact, "impact", "effect");
+amount_pair!(total_fees, "total-fees", "total-fees-effect");
+amount_pair!(price, "price", "price-effect", optional);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub struct BuyingPowerEffect {
-    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
-    pub change_in_margin_requirement: Decimal,
-    pub change_in_margin_requirement_effect: PriceEffect,
-    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
-    pub change_in_buying_power: Decimal,
-    pub change_in_buying_power_effect: PriceEffect,
-    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
-    pub current_buying_power: Decimal,
-    pub current_buying_power_effect: PriceEffect,
-    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
-    pub impact: Decimal,
-    pub effect: PriceEffect,
+    #[serde(flatten, with = "change_in_margin_requirement")]
+    pub change_in_margin_requirement: SignedAmount,
+    #[serde(flatten, with = "change_in_buying_power")]
+    pub change_in_buying_power: SignedAmount,
+    #[serde(flatten, with = "current_buying_power")]
+    pub current_buying_power: SignedAmount,
+    #[serde(flatten, with = "impact")]
+    pub impact: SignedAmount,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub struct FeeCalculation {
-    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
-    pub total_fees: Decimal,
-    pub total_fees_effect: PriceEffect,
+    #[serde(flatten, with = "total_fees")]
+    pub total_fees: SignedAmount,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "kebab-case")]
-pub struct Warning {}
+pub struct Warning {
+    pub code: String,
+    pub message: String,
+    #[serde(default)]
+    pub preflight_id: Option<String>,
+}
+
+/// Conditions under which [`crate::accounts::Account::place_order`] refuses to submit an order.
+///
+/// When a policy is set on an account, every order is dry-run first and only
+/// submitted if [`WarningPolicy::check`] finds no violations.
+#[derive(Debug, Clone, Default)]
+pub struct WarningPolicy {
+    pub blocked_codes: HashSet<String>,
+    pub max_buying_power_impact: Option<Decimal>,
+}
+
+impl WarningPolicy {
+    pub fn block_code(mut self, code: impl Into<String>) -> Self {
+        self.blocked_codes.insert(code.into());
+        self
+    }
+
+    pub fn max_buying_power_impact(mut self, limit: Decimal) -> Self {
+        self.max_buying_power_impact = Some(limit);
+        self
+    }
+
+    pub fn check(&self, dry_run: &DryRunResult) -> Vec<PreflightViolation> {
+        let mut violations: Vec<PreflightViolation> = dry_run
+            .warnings
+            .iter()
+            .filter(|warning| self.blocked_codes.contains(&warning.code))
+            .cloned()
+            .map(PreflightViolation::Warning)
+            .collect();
+
+        let impact = dry_run.buying_power_effect.impact;
+        if let Some(limit) = self.max_buying_power_impact {
+            if impact.is_debit() && impact.amount() > limit {
+                violations.push(PreflightViolation::BuyingPowerImpact {
+                    impact: impact.amount(),
+                    limit,
+                });
+            }
+        }
+        violations
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum PreflightViolation {
+    Warning(Warning),
+    BuyingPowerImpact { impact: Decimal, limit: Decimal },
+}
@@ -0,0 +1,453 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use rust_decimal::Decimal;
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::option_chain::NestedOptionChain;
+use super::order::{Action, InstrumentType, Order, OrderLeg, OrderType, PriceEffect, TimeInForce};
+use super::strategy::OptionType;
+
+#[derive(thiserror::Error, Debug)]
+pub enum TemplateError {
+    #[error("TOML error")]
+    Toml(#[from] toml::de::Error),
+    #[error("TOML serialization error")]
+    TomlSerialize(#[from] toml::ser::Error),
+    #[error("JSON error")]
+    Json(#[from] serde_json::Error),
+    #[error("IO error")]
+    Io(#[from] std::io::Error),
+    #[error("Unsupported template file extension: {0:?}")]
+    UnsupportedFormat(Option<String>),
+    #[error("Missing template parameter {0:?}")]
+    MissingParameter(String),
+    #[error("Invalid decimal {0:?}")]
+    InvalidDecimal(String),
+    #[error("Invalid integer {0:?}")]
+    InvalidInteger(String),
+    #[error("Leg has neither a symbol nor an option")]
+    MissingSymbol,
+    #[error("Strike offsets need an option chain and an underlying price")]
+    MissingChain,
+    #[error("Expiration {0} is not in the option chain")]
+    UnknownExpiration(String),
+    #[error("Strike offset {0} is outside the option chain")]
+    StrikeOutOfRange(i64),
+}
+
+/// A template string or number, e.g. `"1.25"`, `1` or `"{price}"`.
+///
+/// Placeholders of the form `{name}` are substituted from [`TemplateParams`].
+/// JSON numbers keep their exact digits. Formats that only offer floats, such
+/// as TOML, must write fractional numbers as strings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateValue(pub String);
+
+impl<T: AsRef<str>> From<T> for TemplateValue {
+    fn from(value: T) -> Self {
+        Self(value.as_ref().to_owned())
+    }
+}
+
+impl TemplateValue {
+    fn render(&self, params: &TemplateParams) -> Result<String, TemplateError> {
+        let mut rendered = String::new();
+        let mut rest = self.0.as_str();
+        while let Some(start) = rest.find('{') {
+            let Some(len) = rest[start..].find('}') else {
+                break;
+            };
+            let name = &rest[start + 1..start + len];
+            let value = params
+                .values
+                .get(name)
+                .ok_or_else(|| TemplateError::MissingParameter(name.to_owned()))?;
+            rendered.push_str(&rest[..start]);
+            rendered.push_str(value);
+            rest = &rest[start + len + 1..];
+        }
+        rendered.push_str(rest);
+        Ok(rendered)
+    }
+
+    fn render_decimal(&self, params: &TemplateParams) -> Result<Decimal, TemplateError> {
+        let rendered = self.render(params)?;
+        Decimal::from_str_exact(rendered.trim())
+            .or_else(|_| Decimal::from_scientific(rendered.trim()))
+            .map_err(|_| TemplateError::InvalidDecimal(rendered))
+    }
+
+    fn render_integer(&self, params: &TemplateParams) -> Result<i64, TemplateError> {
+        let rendered = self.render(params)?;
+        rendered
+            .trim()
+            .parse()
+            .map_err(|_| TemplateError::InvalidInteger(rendered))
+    }
+}
+
+impl Serialize for TemplateValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for TemplateValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ValueVisitor;
+
+        impl<'de> Visitor<'de> for ValueVisitor {
+            type Value = TemplateValue;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a string, an integer or an exact decimal number")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<TemplateValue, E> {
+                Ok(TemplateValue(v.to_owned()))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<TemplateValue, E> {
+                Ok(TemplateValue(v.to_string()))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<TemplateValue, E> {
+                Ok(TemplateValue(v.to_string()))
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<TemplateValue, E> {
+                Err(E::invalid_type(de::Unexpected::Float(v), &self))
+            }
+
+            /// serde_json passes numbers as a single entry map under
+            /// `arbitrary_precision`, with the number's digits as written.
+            fn visit_map<A: de::MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> Result<TemplateValue, A::Error> {
+                match map.next_entry::<String, String>()? {
+                    Some((key, number)) if key == JSON_NUMBER_TOKEN => Ok(TemplateValue(number)),
+                    _ => Err(de::Error::invalid_type(de::Unexpected::Map, &self)),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+/// The key under which serde_json's `arbitrary_precision` feature passes numbers.
+const JSON_NUMBER_TOKEN: &str = "$serde_json::private::Number";
+
+/// Values substituted into an [`OrderTemplate`] when it is rendered.
+#[derive(Debug, Default, Clone)]
+pub struct TemplateParams<'c> {
+    values: HashMap<String, String>,
+    chain: Option<&'c NestedOptionChain>,
+    underlying_price: Option<Decimal>,
+}
+
+impl<'c> TemplateParams<'c> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the value of the `{name}` placeholder.
+    pub fn set(mut self, name: impl Into<String>, value: impl ToString) -> Self {
+        self.values.insert(name.into(), value.to_string());
+        self
+    }
+
+    /// Sets the chain and underlying price that option legs' strike offsets resolve against.
+    pub fn with_chain(mut self, chain: &'c NestedOptionChain, underlying_price: Decimal) -> Self {
+        self.chain = Some(chain);
+        self.underlying_price = Some(underlying_price);
+        self
+    }
+}
+
+/// A reusable order recipe, stored as TOML or JSON.
+///
+/// ```toml
+/// time-in-force = "Day"
+/// order-type = "Limit"
+/// price = "{price}"
+/// price-effect = "Debit"
+///
+/// [[legs]]
+/// instrument-type = "Equity Option"
+/// quantity = 1
+/// action = "Buy to Open"
+/// option = { option-type = "Call", expiration = "{expiration}", strike-offset = 1 }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct OrderTemplate {
+    pub time_in_force: TimeInForce,
+    pub order_type: OrderType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price: Option<TemplateValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price_effect: Option<PriceEffect>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_trigger: Option<TemplateValue>,
+    /// Dollar amount of notional market orders.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<TemplateValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_effect: Option<PriceEffect>,
+    pub legs: Vec<LegTemplate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct LegTemplate {
+    pub instrument_type: InstrumentType,
+    /// A fixed symbol such as `"{symbol}"`. Ignored when `option` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<TemplateValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub option: Option<OptionLegTemplate>,
+    /// Left out for notional market orders, which trade the order's value instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<TemplateValue>,
+    pub action: Action,
+}
+
+/// An option leg chosen from the chain passed to [`TemplateParams::with_chain`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct OptionLegTemplate {
+    pub option_type: OptionType,
+    /// Expiration date as `YYYY-MM-DD`.
+    pub expiration: TemplateValue,
+    /// Number of strikes above (positive) or below (negative) the strike
+    /// nearest to the underlying price, e.g. `2` or `"{offset}"`.
+    #[serde(default = "zero_offset")]
+    pub strike_offset: TemplateValue,
+}
+
+fn zero_offset() -> TemplateValue {
+    "0".into()
+}
+
+impl OrderTemplate {
+    pub fn from_toml(toml: &str) -> Result<Self, TemplateError> {
+        Ok(toml::from_str(toml)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, TemplateError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_toml(&self) -> Result<String, TemplateError> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+
+    pub fn to_json(&self) -> Result<String, TemplateError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Reads a template from a `.toml` or `.json` file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TemplateError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+        match extension(path).as_deref() {
+            Some("toml") => Self::from_toml(&contents),
+            Some("json") => Self::from_json(&contents),
+            other => Err(TemplateError::UnsupportedFormat(other.map(str::to_owned))),
+        }
+    }
+
+    /// Writes the template to a `.toml` or `.json` file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), TemplateError> {
+        let path = path.as_ref();
+        let contents = match extension(path).as_deref() {
+            Some("toml") => self.to_toml()?,
+            Some("json") => self.to_json()?,
+            other => return Err(TemplateError::UnsupportedFormat(other.map(str::to_owned))),
+        };
+        Ok(std::fs::write(path, contents)?)
+    }
+
+    /// Substitutes `params` into the template, producing an order ready to submit.
+    pub fn render(&self, params: &TemplateParams) -> Result<Order, TemplateError> {
+        let legs = self
+            .legs
+            .iter()
+            .map(|leg| leg.render(params))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Order {
+            time_in_force: self.time_in_force.clone(),
+            order_type: self.order_type.clone(),
+            price: self
+                .price
+                .as_ref()
+                .map(|price| price.render_decimal(params))
+                .transpose()?,
+            price_effect: self.price_effect.clone(),
+            stop_trigger: self
+                .stop_trigger
+                .as_ref()
+                .map(|trigger| trigger.render_decimal(params))
+                .transpose()?,
+            value: self
+                .value
+                .as_ref()
+                .map(|value| value.render_decimal(params))
+                .transpose()?,
+            value_effect: self.value_effect.clone(),
+            external_identifier: None,
+            legs,
+        })
+    }
+}
+
+impl LegTemplate {
+    fn render(&self, params: &TemplateParams) -> Result<OrderLeg, TemplateError> {
+        let symbol = match (&self.option, &self.symbol) {
+            (Some(option), _) => option.resolve(params)?,
+            (None, Some(symbol)) => symbol.render(params)?,
+            (None, None) => return Err(TemplateError::MissingSymbol),
+        };
+        Ok(OrderLeg {
+            instrument_type: self.instrument_type.clone(),
+            symbol: symbol.into(),
+            quantity: self
+                .quantity
+                .as_ref()
+                .map(|quantity| quantity.render_decimal(params))
+                .transpose()?,
+            action: self.action.clone(),
+        })
+    }
+}
+
+impl OptionLegTemplate {
+    fn resolve(&self, params: &TemplateParams) -> Result<String, TemplateError> {
+        let (Some(chain), Some(underlying_price)) = (params.chain, params.underlying_price) else {
+            return Err(TemplateError::MissingChain);
+        };
+        let date = self.expiration.render(params)?;
+        let offset = self.strike_offset.render_integer(params)?;
+        let expiration = chain
+            .expirations
+            .iter()
+            .find(|expiration| expiration.expiration_date == date)
+            .ok_or(TemplateError::UnknownExpiration(date))?;
+
+        let mut strikes: Vec<_> = expiration.strikes.iter().collect();
+        strikes.sort_by_key(|strike| strike.strike_price);
+        let at_the_money = strikes
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, strike)| (strike.strike_price - underlying_price).abs())
+            .map(|(index, _)| index as i64)
+            .ok_or(TemplateError::StrikeOutOfRange(offset))?;
+        let strike = at_the_money
+            .checked_add(offset)
+            .and_then(|index| usize::try_from(index).ok())
+            .and_then(|index| strikes.get(index))
+            .ok_or(TemplateError::StrikeOutOfRange(offset))?;
+        Ok(strike.symbol(self.option_type).0.clone())
+    }
+}
+
+impl From<&Order> for OrderTemplate {
+    /// A template with no placeholders that renders back to `order`.
+    fn from(order: &Order) -> Self {
+        Self {
+            time_in_force: order.time_in_force.clone(),
+            order_type: order.order_type.clone(),
+            price: order.price.map(|price| price.to_string().into()),
+            price_effect: order.price_effect.clone(),
+            stop_trigger: order.stop_trigger.map(|trigger| trigger.to_string().into()),
+            value: order.value.map(|value| value.to_string().into()),
+            value_effect: order.value_effect.clone(),
+            legs: order
+                .legs
+                .iter()
+                .map(|leg| LegTemplate {
+                    instrument_type: leg.instrument_type.clone(),
+                    symbol: Some(leg.symbol.0.as_str().into()),
+                    option: None,
+                    quantity: leg.quantity.map(|quantity| quantity.to_string().into()),
+                    action: leg.action.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(template: &OrderTemplate) -> Decimal {
+        template
+            .render(&TemplateParams::new())
+            .unwrap()
+            .price
+            .unwrap()
+    }
+
+    const JSON: &str = r#"{
+        "time-in-force": "Day",
+        "order-type": "Limit",
+        "price": 0.30000000000000000001,
+        "price-effect": "Debit",
+        "legs": [{ "instrument-type": "Equity", "symbol": "SPY", "quantity": 100, "action": "Buy to Open" }]
+    }"#;
+
+    #[test]
+    fn keeps_exact_json_numbers() {
+        let template = OrderTemplate::from_json(JSON).unwrap();
+        assert_eq!(
+            price(&template),
+            Decimal::from_str_exact("0.30000000000000000001").unwrap()
+        );
+        let reparsed = OrderTemplate::from_json(&template.to_json().unwrap()).unwrap();
+        assert_eq!(price(&reparsed), price(&template));
+    }
+
+    #[test]
+    fn requires_strings_for_toml_fractions() {
+        let toml = r#"
+            time-in-force = "Day"
+            order-type = "Limit"
+            price = 1.1
+            legs = []
+        "#;
+        assert!(OrderTemplate::from_toml(toml).is_err());
+
+        let template = OrderTemplate::from_toml(&toml.replace("1.1", "\"1.1\"")).unwrap();
+        assert_eq!(price(&template), Decimal::new(11, 1));
+    }
+
+    #[test]
+    fn renders_placeholder_strike_offset() {
+        let toml = r#"
+            option-type = "Call"
+            expiration = "{expiration}"
+            strike-offset = "{offset}"
+        "#;
+        let option: OptionLegTemplate = toml::from_str(toml).unwrap();
+        let params = TemplateParams::new().set("offset", -2);
+        assert_eq!(option.strike_offset.render_integer(&params).unwrap(), -2);
+        assert!(matches!(
+            option
+                .strike_offset
+                .render_integer(&TemplateParams::new().set("offset", "x")),
+            Err(TemplateError::InvalidInteger(_))
+        ));
+    }
+}
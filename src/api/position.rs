@@ -3,16 +3,25 @@ use serde::{Deserialize, Serialize};
 
 use crate::accounts::AccountNumber;
 
-use super::order::{InstrumentType, PriceEffect, Symbol};
+use super::money::SignedAmount;
+use super::order::{Action, InstrumentType, OrderLeg, PriceEffect, Symbol};
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
-pub enum QuantityDirection {
-    Long,
-    Short,
-    Zero,
+api_enum! {
+    pub enum QuantityDirection {
+        Long => "Long",
+        Short => "Short",
+        Zero => "Zero",
+    }
 }
 
-#[derive(Debug, Deserialize)]
+amount_pair!(
+    realized_day_gain,
+    "realized-day-gain",
+    "realized-day-gain-effect"
+);
+amount_pair!(realized_today, "realized-today", "realized-today-effect");
+
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct FullPosition {
     pub account_number: AccountNumber,
@@ -37,17 +46,82 @@ pub struct FullPosition {
     pub is_frozen: bool,
     #[serde(with = "rust_decimal::serde::arbitrary_precision")]
     pub restricted_quantity: Decimal,
-    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
-    pub realized_day_gain: Decimal,
-    pub realized_day_gain_effect: String,
+    #[serde(flatten, with = "realized_day_gain")]
+    pub realized_day_gain: SignedAmount,
     pub realized_day_gain_date: String,
-    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
-    pub realized_today: Decimal,
-    pub realized_today_effect: String,
+    #[serde(flatten, with = "realized_today")]
+    pub realized_today: SignedAmount,
     pub realized_today_date: String,
     pub created_at: String,
     pub updated_at: String,
 }
+
+impl FullPosition {
+    /// Total cost of opening this position, signed by `cost_effect`.
+    pub fn cost(&self) -> SignedAmount {
+        SignedAmount::new(
+            self.average_open_price * self.quantity.abs() * self.multiplier,
+            &self.cost_effect,
+        )
+    }
+
+    /// The action that closes this position, or `None` if it is flat.
+    /// Futures are closed with plain `Buy`/`Sell`, everything else with the to-close actions.
+    pub fn closing_action(&self) -> Option<Action> {
+        let action = match (&self.quantity_direction, &self.instrument_type) {
+            (QuantityDirection::Zero | QuantityDirection::Unknown(_), _) => return None,
+            (QuantityDirection::Long, InstrumentType::Future) => Action::Sell,
+            (QuantityDirection::Short, InstrumentType::Future) => Action::Buy,
+            (QuantityDirection::Long, _) => Action::SellToClose,
+            (QuantityDirection::Short, _) => Action::BuyToClose,
+        };
+        Some(action)
+    }
+
+    /// An order leg closing the whole position, or `None` if it is flat.
+    pub fn closing_leg(&self) -> Option<OrderLeg> {
+        Some(OrderLeg {
+            instrument_type: self.instrument_type.clone(),
+            symbol: self.symbol.clone(),
+            quantity: Some(self.quantity.abs()),
+            action: self.closing_action()?,
+        })
+    }
+
+    /// A position for tests, short when `quantity` is negative, with zero prices.
+    #[cfg(test)]
+    pub(crate) fn test(instrument_type: InstrumentType, symbol: &str, quantity: i64) -> Self {
+        let direction = match quantity.signum() {
+            1 => QuantityDirection::Long,
+            -1 => QuantityDirection::Short,
+            _ => QuantityDirection::Zero,
+        };
+        Self {
+            account_number: "5WT00000".into(),
+            symbol: symbol.into(),
+            instrument_type,
+            underlying_symbol: "SPY".into(),
+            quantity: Decimal::from(quantity.abs()),
+            quantity_direction: direction,
+            close_price: Decimal::ZERO,
+            average_open_price: Decimal::ZERO,
+            average_yearly_market_close_price: Decimal::ZERO,
+            average_daily_market_close_price: Decimal::ZERO,
+            multiplier: Decimal::ONE,
+            cost_effect: PriceEffect::Debit,
+            is_suppressed: false,
+            is_frozen: false,
+            restricted_quantity: Decimal::ZERO,
+            realized_day_gain: SignedAmount::ZERO,
+            realized_day_gain_date: String::new(),
+            realized_today: SignedAmount::ZERO,
+            realized_today_date: String::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct BriefPosition {
@@ -67,12 +141,12 @@ pub struct BriefPosition {
     pub cost_effect: PriceEffect,
     pub is_suppressed: bool,
     pub is_frozen: bool,
-    #[serde(with = "rust_decimal::serde::float")]
-    pub restricted_quantity: Decimal,
-    #[serde(with = "rust_decimal::serde::arbitrary_precision")]
-    pub realized_day_gain: Decimal,
     #[serde(with = "rust_decimal::serde::arbitrary_precision")]
-    pub realized_today: Decimal,
+    pub restricted_quantity: Decimal,
+    #[serde(flatten, with = "realized_day_gain")]
+    pub realized_day_gain: SignedAmount,
+    #[serde(flatten, with = "realized_today")]
+    pub realized_today: SignedAmount,
     pub created_at: String,
     pub updated_at: String,
 }
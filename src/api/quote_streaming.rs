@@ -4,6 +4,7 @@ use serde::Serialize;
 use crate::Result;
 use crate::TastyTrade;
 
+use super::base::TastyError;
 use super::order::AsSymbol;
 use super::order::InstrumentType;
 use super::order::Symbol;
@@ -46,11 +47,21 @@ impl TastyTrade {
         symbol: &Symbol,
     ) -> Result<DxFeedSymbol> {
         use InstrumentType::*;
+        let cache = self.instrument_cache();
+        if let Some(sym) = cache.and_then(|cache| cache.streamer_symbol(instrument_type, symbol)) {
+            return Ok(sym);
+        }
         let sym = match instrument_type {
             Equity => self.get_equity_info(symbol).await?.streamer_symbol,
             EquityOption => self.get_option_info(symbol).await?.streamer_symbol,
-            _ => unimplemented!(),
+            Future => self.get_future(symbol).await?.streamer_symbol,
+            FutureOption => self.get_future_option(symbol).await?.streamer_symbol,
+            Cryptocurrency => self.get_cryptocurrency(symbol).await?.streamer_symbol,
+            _ => return Err(TastyError::UnsupportedInstrument(instrument_type.clone())),
         };
+        if let Some(cache) = cache {
+            cache.insert_streamer_symbol(instrument_type.clone(), symbol.clone(), sym.clone());
+        }
         Ok(sym)
     }
 }
@@ -0,0 +1,741 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use super::order::{Action, DryRunResult, InstrumentType, Order, OrderLeg, Symbol};
+use super::position::{FullPosition, QuantityDirection};
+use super::strategy::OptionType;
+use super::symbology::{FutureOptionSymbol, FutureSymbol, OptionSymbol};
+
+/// Shares delivered per standard equity option contract.
+const SHARES_PER_CONTRACT: Decimal = Decimal::ONE_HUNDRED;
+
+/// Hard limits that [`crate::accounts::Account::place_order`] enforces before submitting.
+///
+/// Unset limits are not checked. Orders are dry-run first so the buying power
+/// impact is known.
+#[derive(Debug, Clone, Default)]
+pub struct RiskLimits {
+    /// Largest allowed `price * size * multiplier`, where the multiplier is 100
+    /// for equity options, the product's entry in `futures_multipliers` for
+    /// futures and future options, and 1 otherwise, or the value of notional
+    /// market orders. Orders whose notional is unknown are rejected when this
+    /// is set. Orders that only close positions are not checked.
+    pub max_order_notional: Option<Decimal>,
+    /// Contract multipliers of futures products, keyed by product code such as
+    /// `ES`, e.g. from [`super::instrument::FutureInstrument::notional_multiplier`].
+    pub futures_multipliers: HashMap<String, Decimal>,
+    /// Largest allowed number of option and futures contracts across all legs.
+    pub max_contracts_per_order: Option<Decimal>,
+    /// Largest allowed number of orders per day, counting every order placed
+    /// since midnight UTC in the account's order history, whatever its state.
+    pub max_daily_orders: Option<usize>,
+    /// When set, only these underlyings may be traded.
+    pub allowed_underlyings: Option<HashSet<Symbol>>,
+    pub blocked_underlyings: HashSet<Symbol>,
+    pub max_buying_power_impact: Option<Decimal>,
+    /// Reject short equity and future options not covered by long options,
+    /// stock or futures in the same order or in existing positions.
+    pub forbid_naked_short_options: bool,
+}
+
+impl RiskLimits {
+    pub fn max_order_notional(mut self, limit: Decimal) -> Self {
+        self.max_order_notional = Some(limit);
+        self
+    }
+
+    pub fn futures_multiplier(mut self, product_code: &str, multiplier: Decimal) -> Self {
+        self.futures_multipliers
+            .insert(product_code.trim_start_matches('/').to_owned(), multiplier);
+        self
+    }
+
+    pub fn max_contracts_per_order(mut self, limit: Decimal) -> Self {
+        self.max_contracts_per_order = Some(limit);
+        self
+    }
+
+    pub fn max_daily_orders(mut self, limit: usize) -> Self {
+        self.max_daily_orders = Some(limit);
+        self
+    }
+
+    pub fn allow_underlying(mut self, symbol: impl Into<Symbol>) -> Self {
+        self.allowed_underlyings
+            .get_or_insert_with(HashSet::new)
+            .insert(symbol.into());
+        self
+    }
+
+    pub fn block_underlying(mut self, symbol: impl Into<Symbol>) -> Self {
+        self.blocked_underlyings.insert(symbol.into());
+        self
+    }
+
+    pub fn max_buying_power_impact(mut self, limit: Decimal) -> Self {
+        self.max_buying_power_impact = Some(limit);
+        self
+    }
+
+    pub fn forbid_naked_short_options(mut self) -> Self {
+        self.forbid_naked_short_options = true;
+        self
+    }
+
+    /// Whether [`RiskLimits::check`] needs the account's positions.
+    pub fn needs_positions(&self) -> bool {
+        self.forbid_naked_short_options || self.max_order_notional.is_some()
+    }
+
+    /// Checks an order and its dry run against the limits.
+    ///
+    /// `orders_today` is the number of orders already placed today.
+    pub fn check(
+        &self,
+        order: &Order,
+        dry_run: &DryRunResult,
+        positions: &[FullPosition],
+        orders_today: usize,
+    ) -> Vec<RiskViolation> {
+        let mut violations = vec![];
+
+        let closing =
+            !order.legs().is_empty() && order.legs().iter().all(|leg| is_closing(leg, positions));
+        if let Some(limit) = self.max_order_notional.filter(|_| !closing) {
+            let notional = match (order.value(), order.price()) {
+                (Some(value), _) => Some(value.abs()),
+                (None, Some(price)) => self
+                    .multiplier(order)
+                    .map(|multiplier| price.abs() * dry_run.order.size * multiplier),
+                (None, None) => None,
+            };
+            match notional {
+                Some(notional) => {
+                    if notional > limit {
+                        violations.push(RiskViolation::OrderNotional { notional, limit });
+                    }
+                }
+                None => violations.push(RiskViolation::UnknownNotional),
+            }
+        }
+
+        if let Some(limit) = self.max_contracts_per_order {
+            let contracts: Decimal = order
+                .legs()
+                .iter()
+                .filter(|leg| is_contract(leg.instrument_type()))
+                .filter_map(|leg| leg.quantity())
+                .sum();
+            if contracts > limit {
+                violations.push(RiskViolation::ContractsPerOrder { contracts, limit });
+            }
+        }
+
+        if let Some(limit) = self.max_daily_orders {
+            if orders_today >= limit {
+                violations.push(RiskViolation::DailyOrders {
+                    count: orders_today,
+                    limit,
+                });
+            }
+        }
+
+        let underlying = &dry_run.order.underlying_symbol;
+        if self
+            .allowed_underlyings
+            .as_ref()
+            .is_some_and(|allowed| !allowed.contains(underlying))
+        {
+            violations.push(RiskViolation::UnderlyingNotAllowed(underlying.clone()));
+        }
+        if self.blocked_underlyings.contains(underlying) {
+            violations.push(RiskViolation::UnderlyingBlocked(underlying.clone()));
+        }
+
+        let impact = dry_run.buying_power_effect.impact;
+        if let Some(limit) = self.max_buying_power_impact {
+            if impact.is_debit() && impact.amount() > limit {
+                violations.push(RiskViolation::BuyingPowerImpact {
+                    impact: impact.amount(),
+                    limit,
+                });
+            }
+        }
+
+        if self.forbid_naked_short_options {
+            violations.extend(
+                naked_short_options(order.legs(), positions)
+                    .into_iter()
+                    .map(RiskViolation::NakedShortOption),
+            );
+        }
+
+        violations
+    }
+}
+
+/// A [`RiskLimits`] rule that an order breaks.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum RiskViolation {
+    #[error("Order notional {notional} exceeds the limit of {limit}")]
+    OrderNotional { notional: Decimal, limit: Decimal },
+    #[error("Order has no price or an unknown multiplier, so its notional cannot be checked")]
+    UnknownNotional,
+    #[error("Order has {contracts} contracts, the limit is {limit}")]
+    ContractsPerOrder { contracts: Decimal, limit: Decimal },
+    #[error("{count} orders were already placed today, the limit is {limit}")]
+    DailyOrders { count: usize, limit: usize },
+    #[error("Underlying {0} is not in the allowed list")]
+    UnderlyingNotAllowed(Symbol),
+    #[error("Underlying {0} is blocked")]
+    UnderlyingBlocked(Symbol),
+    #[error("Buying power impact {impact} exceeds the limit of {limit}")]
+    BuyingPowerImpact { impact: Decimal, limit: Decimal },
+    #[error("Short option {0} is not covered")]
+    NakedShortOption(Symbol),
+}
+
+fn is_contract(instrument_type: &InstrumentType) -> bool {
+    matches!(
+        instrument_type,
+        InstrumentType::EquityOption | InstrumentType::Future | InstrumentType::FutureOption
+    )
+}
+
+impl RiskLimits {
+    /// The largest multiplier of the order's legs, or `None` for futures
+    /// products without a known multiplier.
+    fn multiplier(&self, order: &Order) -> Option<Decimal> {
+        let mut multiplier = Decimal::ONE;
+        for leg in order.legs() {
+            let leg_multiplier = match leg.instrument_type() {
+                InstrumentType::EquityOption => SHARES_PER_CONTRACT,
+                InstrumentType::Future | InstrumentType::FutureOption => {
+                    *self.futures_multipliers.get(&future_product_code(leg)?)?
+                }
+                _ => Decimal::ONE,
+            };
+            multiplier = multiplier.max(leg_multiplier);
+        }
+        Some(multiplier)
+    }
+}
+
+fn future_product_code(leg: &OrderLeg) -> Option<String> {
+    let future = match leg.instrument_type() {
+        InstrumentType::Future => FutureSymbol::try_from(leg.symbol()).ok()?,
+        _ => FutureOptionSymbol::try_from(leg.symbol()).ok()?.underlying,
+    };
+    Some(future.product_code)
+}
+
+/// Whether a leg reduces an existing position. Futures are closed with plain
+/// buys and sells, so those count when they offset a position in `positions`.
+fn is_closing(leg: &OrderLeg, positions: &[FullPosition]) -> bool {
+    match leg.action() {
+        Action::BuyToClose | Action::SellToClose => true,
+        Action::Buy | Action::Sell => positions.iter().any(|position| {
+            &position.symbol == leg.symbol()
+                && position.closing_action().as_ref() == Some(leg.action())
+        }),
+        _ => false,
+    }
+}
+
+/// The underlying and type an option must share with whatever covers it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CoverKey {
+    underlying: String,
+    option_type: OptionType,
+}
+
+/// Quantities available to cover short options, by the expiration of the
+/// covering option, or `None` for the underlying itself.
+type Cover = HashMap<CoverKey, Vec<(Option<NaiveDate>, Decimal)>>;
+
+/// Short option legs of the order that are not covered, in leg order.
+///
+/// Long options of the same underlying and type expiring no earlier than the
+/// short cover it, as does long stock (100 shares per contract) or a long
+/// future (one per contract) for calls. Cover comes from the order's buy legs
+/// and the long positions in `positions`, less what the short option positions
+/// in `positions` already use. Short legs whose symbol cannot be parsed are
+/// reported, since their cover cannot be checked.
+fn naked_short_options(legs: &[OrderLeg], positions: &[FullPosition]) -> Vec<Symbol> {
+    let mut cover = Cover::new();
+    let mut existing_shorts = vec![];
+    for position in positions {
+        let quantity = position.quantity.abs();
+        match position.quantity_direction {
+            QuantityDirection::Long => add_cover(
+                &mut cover,
+                &position.instrument_type,
+                &position.symbol,
+                quantity,
+            ),
+            QuantityDirection::Short => {
+                if let Some(contract) = option_contract(&position.instrument_type, &position.symbol)
+                {
+                    existing_shorts.push((contract, quantity));
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut shorts = vec![];
+    for (index, leg) in legs.iter().enumerate() {
+        let quantity = leg.quantity().unwrap_or_default();
+        match leg.action() {
+            Action::BuyToOpen | Action::Buy => {
+                add_cover(&mut cover, leg.instrument_type(), leg.symbol(), quantity)
+            }
+            Action::SellToOpen if is_option(leg.instrument_type()) => shorts.push((
+                index,
+                option_contract(leg.instrument_type(), leg.symbol()),
+                quantity,
+            )),
+            _ => {}
+        }
+    }
+
+    // Existing positions keep their cover. Later expirations go first, as
+    // fewer options can cover them.
+    existing_shorts.sort_by(|((_, a), _), ((_, b), _)| b.cmp(a));
+    for ((key, expiration), quantity) in existing_shorts {
+        use_cover(&mut cover, &key, expiration, quantity);
+    }
+    shorts.sort_by(|(_, a, _), (_, b, _)| {
+        let expiration = |contract: &Option<(CoverKey, NaiveDate)>| contract.as_ref().map(|c| c.1);
+        expiration(b).cmp(&expiration(a))
+    });
+    let mut naked: Vec<usize> = shorts
+        .into_iter()
+        .filter(|(_, contract, quantity)| match contract {
+            Some((key, expiration)) => !use_cover(&mut cover, key, *expiration, *quantity),
+            None => true,
+        })
+        .map(|(index, _, _)| index)
+        .collect();
+    naked.sort_unstable();
+    naked
+        .into_iter()
+        .map(|index| legs[index].symbol().clone())
+        .collect()
+}
+
+fn is_option(instrument_type: &InstrumentType) -> bool {
+    matches!(
+        instrument_type,
+        InstrumentType::EquityOption | InstrumentType::FutureOption
+    )
+}
+
+/// The cover key and expiration of an equity or future option.
+fn option_contract(
+    instrument_type: &InstrumentType,
+    symbol: &Symbol,
+) -> Option<(CoverKey, NaiveDate)> {
+    let (underlying, option_type, expiration) = match instrument_type {
+        InstrumentType::EquityOption => {
+            let option = OptionSymbol::try_from(symbol).ok()?;
+            (option.root, option.option_type, option.expiration)
+        }
+        InstrumentType::FutureOption => {
+            let option = FutureOptionSymbol::try_from(symbol).ok()?;
+            (
+                option.underlying.to_string(),
+                option.option_type,
+                option.expiration,
+            )
+        }
+        _ => return None,
+    };
+    let key = CoverKey {
+        underlying,
+        option_type,
+    };
+    Some((key, expiration))
+}
+
+fn add_cover(
+    cover: &mut Cover,
+    instrument_type: &InstrumentType,
+    symbol: &Symbol,
+    quantity: Decimal,
+) {
+    let (key, expiration, quantity) = match instrument_type {
+        InstrumentType::EquityOption | InstrumentType::FutureOption => {
+            let Some((key, expiration)) = option_contract(instrument_type, symbol) else {
+                return;
+            };
+            (key, Some(expiration), quantity)
+        }
+        InstrumentType::Equity => {
+            let key = CoverKey {
+                underlying: symbol.0.clone(),
+                option_type: OptionType::Call,
+            };
+            (key, None, (quantity / SHARES_PER_CONTRACT).floor())
+        }
+        InstrumentType::Future => {
+            let Ok(future) = FutureSymbol::try_from(symbol) else {
+                return;
+            };
+            let key = CoverKey {
+                underlying: future.to_string(),
+                option_type: OptionType::Call,
+            };
+            (key, None, quantity)
+        }
+        _ => return,
+    };
+    cover.entry(key).or_default().push((expiration, quantity));
+}
+
+/// Takes `quantity` from cover expiring no earlier than `expiration`, soonest
+/// first. Returns whether it was fully covered.
+fn use_cover(cover: &mut Cover, key: &CoverKey, expiration: NaiveDate, quantity: Decimal) -> bool {
+    let mut remaining = quantity;
+    if let Some(available) = cover.get_mut(key) {
+        available.sort_by_key(|(expires, _)| expires.unwrap_or(NaiveDate::MAX));
+        for (expires, available) in available.iter_mut() {
+            if expires.is_some_and(|expires| expires < expiration) {
+                continue;
+            }
+            let used = remaining.min(*available);
+            *available -= used;
+            remaining -= used;
+        }
+    }
+    remaining <= Decimal::ZERO
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::money::SignedAmount;
+    use crate::api::order::{
+        BuyingPowerEffect, DryRunRecord, FeeCalculation, OrderLegBuilder, OrderStatus, OrderType,
+        PriceEffect, TimeInForce,
+    };
+
+    fn leg(
+        instrument_type: InstrumentType,
+        symbol: &str,
+        quantity: i64,
+        action: Action,
+    ) -> OrderLeg {
+        OrderLegBuilder::default()
+            .instrument_type(instrument_type)
+            .symbol(symbol)
+            .quantity(Decimal::from(quantity))
+            .action(action)
+            .build()
+            .unwrap()
+    }
+
+    const CALL_JUN: &str = "SPY   230616C00450000";
+    const CALL_SEP: &str = "SPY   230915C00460000";
+    const PUT_JUN: &str = "SPY   230616P00400000";
+
+    fn sell_call(symbol: &str) -> OrderLeg {
+        leg(InstrumentType::EquityOption, symbol, 1, Action::SellToOpen)
+    }
+
+    #[test]
+    fn flags_uncovered_short_option() {
+        let naked = naked_short_options(&[sell_call(CALL_JUN)], &[]);
+        assert_eq!(naked, vec![Symbol::from(CALL_JUN)]);
+    }
+
+    #[test]
+    fn long_option_in_order_covers_short() {
+        let legs = [
+            leg(InstrumentType::EquityOption, CALL_SEP, 1, Action::BuyToOpen),
+            sell_call(CALL_JUN),
+        ];
+        assert!(naked_short_options(&legs, &[]).is_empty());
+    }
+
+    #[test]
+    fn long_option_expiring_sooner_does_not_cover() {
+        let legs = [
+            leg(InstrumentType::EquityOption, CALL_JUN, 1, Action::BuyToOpen),
+            sell_call(CALL_SEP),
+        ];
+        assert_eq!(
+            naked_short_options(&legs, &[]),
+            vec![Symbol::from(CALL_SEP)]
+        );
+    }
+
+    #[test]
+    fn calls_do_not_cover_puts() {
+        let legs = [
+            leg(InstrumentType::EquityOption, CALL_JUN, 1, Action::BuyToOpen),
+            leg(InstrumentType::EquityOption, PUT_JUN, 1, Action::SellToOpen),
+        ];
+        assert_eq!(naked_short_options(&legs, &[]), vec![Symbol::from(PUT_JUN)]);
+    }
+
+    #[test]
+    fn stock_covers_calls() {
+        let positions = [FullPosition::test(InstrumentType::Equity, "SPY", 150)];
+        assert!(naked_short_options(&[sell_call(CALL_JUN)], &positions).is_empty());
+
+        let legs = [
+            leg(InstrumentType::Equity, "SPY", 100, Action::Buy),
+            sell_call(CALL_JUN),
+        ];
+        assert!(naked_short_options(&legs, &[]).is_empty());
+    }
+
+    #[test]
+    fn existing_short_options_use_up_cover() {
+        let positions = [
+            FullPosition::test(InstrumentType::Equity, "SPY", 100),
+            FullPosition::test(InstrumentType::EquityOption, CALL_SEP, -1),
+        ];
+        assert_eq!(
+            naked_short_options(&[sell_call(CALL_JUN)], &positions),
+            vec![Symbol::from(CALL_JUN)]
+        );
+
+        let positions = [
+            FullPosition::test(InstrumentType::EquityOption, CALL_SEP, 1),
+            FullPosition::test(InstrumentType::EquityOption, CALL_JUN, -1),
+        ];
+        assert_eq!(
+            naked_short_options(&[sell_call(CALL_JUN)], &positions),
+            vec![Symbol::from(CALL_JUN)]
+        );
+    }
+
+    #[test]
+    fn checks_future_options() {
+        let short = "./ESZ3 EW4U3 230929C4500";
+        let sell = leg(InstrumentType::FutureOption, short, 1, Action::SellToOpen);
+        assert_eq!(
+            naked_short_options(std::slice::from_ref(&sell), &[]),
+            vec![Symbol::from(short)]
+        );
+
+        let long = leg(
+            InstrumentType::FutureOption,
+            "./ESZ3 ESZ3 231215C4600",
+            1,
+            Action::BuyToOpen,
+        );
+        assert!(naked_short_options(&[long, sell.clone()], &[]).is_empty());
+
+        let positions = [FullPosition::test(InstrumentType::Future, "/ESZ3", 1)];
+        assert!(naked_short_options(&[sell], &positions).is_empty());
+    }
+
+    #[test]
+    fn flags_unparsable_short_options() {
+        let sell = leg(InstrumentType::FutureOption, "./ES", 1, Action::SellToOpen);
+        assert_eq!(
+            naked_short_options(&[sell], &[]),
+            vec![Symbol::from("./ES")]
+        );
+    }
+
+    fn order(price: Option<i64>, legs: Vec<OrderLeg>) -> Order {
+        Order {
+            time_in_force: TimeInForce::Day,
+            order_type: OrderType::Limit,
+            price: price.map(Decimal::from),
+            price_effect: Some(PriceEffect::Debit),
+            stop_trigger: None,
+            value: None,
+            value_effect: None,
+            external_identifier: None,
+            legs,
+        }
+    }
+
+    fn dry_run(size: i64, underlying: &str, impact: SignedAmount) -> DryRunResult {
+        DryRunResult {
+            order: DryRunRecord {
+                account_number: "5WT00000".into(),
+                time_in_force: TimeInForce::Day,
+                order_type: OrderType::Limit,
+                size: Decimal::from(size),
+                underlying_symbol: underlying.into(),
+                price: None,
+                status: OrderStatus::Received,
+                cancellable: true,
+                editable: true,
+                edited: false,
+                legs: vec![],
+            },
+            warnings: vec![],
+            buying_power_effect: BuyingPowerEffect {
+                change_in_margin_requirement: SignedAmount::ZERO,
+                change_in_buying_power: impact,
+                current_buying_power: SignedAmount::ZERO,
+                impact,
+            },
+            fee_calculation: FeeCalculation {
+                total_fees: SignedAmount::ZERO,
+            },
+        }
+    }
+
+    fn check(limits: &RiskLimits, order: &Order, positions: &[FullPosition]) -> Vec<RiskViolation> {
+        let size = order
+            .legs()
+            .iter()
+            .filter_map(|leg| leg.quantity())
+            .max()
+            .unwrap_or_default();
+        let dry_run = dry_run(size.try_into().unwrap(), "SPY", SignedAmount::ZERO);
+        limits.check(order, &dry_run, positions, 0)
+    }
+
+    fn buy_call(quantity: i64) -> OrderLeg {
+        leg(
+            InstrumentType::EquityOption,
+            CALL_JUN,
+            quantity,
+            Action::BuyToOpen,
+        )
+    }
+
+    #[test]
+    fn limits_option_notional_with_contract_multiplier() {
+        let limits = RiskLimits::default().max_order_notional(Decimal::from(1000));
+        assert!(check(&limits, &order(Some(5), vec![buy_call(2)]), &[]).is_empty());
+        assert_eq!(
+            check(&limits, &order(Some(6), vec![buy_call(2)]), &[]),
+            vec![RiskViolation::OrderNotional {
+                notional: Decimal::from(1200),
+                limit: Decimal::from(1000),
+            }]
+        );
+    }
+
+    #[test]
+    fn limits_futures_notional_with_product_multiplier() {
+        let future = leg(InstrumentType::Future, "/ESZ3", 1, Action::Buy);
+        let order = order(Some(4000), vec![future]);
+        let limits = RiskLimits::default().max_order_notional(Decimal::from(100_000));
+        assert_eq!(
+            check(&limits, &order, &[]),
+            vec![RiskViolation::UnknownNotional]
+        );
+
+        let limits = limits.futures_multiplier("/ES", Decimal::from(50));
+        assert_eq!(
+            check(&limits, &order, &[]),
+            vec![RiskViolation::OrderNotional {
+                notional: Decimal::from(200_000),
+                limit: Decimal::from(100_000),
+            }]
+        );
+    }
+
+    #[test]
+    fn uses_value_of_notional_orders() {
+        let mut order = order(
+            None,
+            vec![leg(InstrumentType::Equity, "SPY", 1, Action::Buy)],
+        );
+        order.value = Some(Decimal::from(-500));
+        let limits = RiskLimits::default().max_order_notional(Decimal::from(400));
+        assert_eq!(
+            check(&limits, &order, &[]),
+            vec![RiskViolation::OrderNotional {
+                notional: Decimal::from(500),
+                limit: Decimal::from(400),
+            }]
+        );
+    }
+
+    #[test]
+    fn skips_notional_of_closing_orders() {
+        let limits = RiskLimits::default().max_order_notional(Decimal::ONE);
+        let close = leg(
+            InstrumentType::EquityOption,
+            CALL_JUN,
+            1,
+            Action::SellToClose,
+        );
+        assert!(check(&limits, &order(None, vec![close]), &[]).is_empty());
+
+        let sell = leg(InstrumentType::Future, "/ESZ3", 1, Action::Sell);
+        let positions = [FullPosition::test(InstrumentType::Future, "/ESZ3", 1)];
+        assert!(check(&limits, &order(None, vec![sell.clone()]), &positions).is_empty());
+        assert_eq!(
+            check(&limits, &order(None, vec![sell]), &[]),
+            vec![RiskViolation::UnknownNotional]
+        );
+        assert_eq!(
+            check(&limits, &order(None, vec![]), &[]),
+            vec![RiskViolation::UnknownNotional]
+        );
+    }
+
+    #[test]
+    fn limits_contracts_but_not_shares() {
+        let limits = RiskLimits::default().max_contracts_per_order(Decimal::from(2));
+        let stock = leg(InstrumentType::Equity, "SPY", 500, Action::Buy);
+        assert!(check(&limits, &order(Some(1), vec![stock, buy_call(2)]), &[]).is_empty());
+        assert_eq!(
+            check(&limits, &order(Some(1), vec![buy_call(3)]), &[]),
+            vec![RiskViolation::ContractsPerOrder {
+                contracts: Decimal::from(3),
+                limit: Decimal::from(2),
+            }]
+        );
+    }
+
+    #[test]
+    fn limits_daily_orders() {
+        let limits = RiskLimits::default().max_daily_orders(3);
+        let order = order(Some(1), vec![buy_call(1)]);
+        let dry_run = dry_run(1, "SPY", SignedAmount::ZERO);
+        assert!(limits.check(&order, &dry_run, &[], 2).is_empty());
+        assert_eq!(
+            limits.check(&order, &dry_run, &[], 3),
+            vec![RiskViolation::DailyOrders { count: 3, limit: 3 }]
+        );
+    }
+
+    #[test]
+    fn limits_underlyings() {
+        let order = order(Some(1), vec![buy_call(1)]);
+        let dry_run = dry_run(1, "SPY", SignedAmount::ZERO);
+        let allowed = RiskLimits::default().allow_underlying("QQQ");
+        assert_eq!(
+            allowed.check(&order, &dry_run, &[], 0),
+            vec![RiskViolation::UnderlyingNotAllowed("SPY".into())]
+        );
+        let blocked = RiskLimits::default().block_underlying("SPY");
+        assert_eq!(
+            blocked.check(&order, &dry_run, &[], 0),
+            vec![RiskViolation::UnderlyingBlocked("SPY".into())]
+        );
+        let both = RiskLimits::default().allow_underlying("SPY");
+        assert!(both.check(&order, &dry_run, &[], 0).is_empty());
+    }
+
+    #[test]
+    fn limits_buying_power_debits_only() {
+        let limits = RiskLimits::default().max_buying_power_impact(Decimal::from(1000));
+        let order = order(Some(1), vec![buy_call(1)]);
+        let debit = dry_run(1, "SPY", SignedAmount::debit(Decimal::from(1500)));
+        assert_eq!(
+            limits.check(&order, &debit, &[], 0),
+            vec![RiskViolation::BuyingPowerImpact {
+                impact: Decimal::from(1500),
+                limit: Decimal::from(1000),
+            }]
+        );
+        let credit = dry_run(1, "SPY", SignedAmount::credit(Decimal::from(1500)));
+        assert!(limits.check(&order, &credit, &[], 0).is_empty());
+    }
+}
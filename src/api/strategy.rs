@@ -0,0 +1,713 @@
+use std::collections::BTreeMap;
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use super::option_chain::{
+    Expiration, FutureOptionExpiration, FutureOptionStrike, NestedOptionChain, Strike,
+};
+use super::order::{
+    Action, InstrumentType, Order, OrderBuilder, OrderLeg, OrderType, PriceEffect, Symbol,
+    TimeInForce, MAX_LEGS,
+};
+use super::position::{FullPosition, QuantityDirection};
+use super::symbology::{FutureOptionSymbol, OptionSymbol};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OptionType {
+    #[serde(alias = "C")]
+    Call,
+    #[serde(alias = "P")]
+    Put,
+}
+
+/// Whether the strategy opens a new position or closes an existing one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionIntent {
+    Open,
+    Close,
+}
+
+/// Whether the strategy is bought (long) or sold (short) as a whole.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrategyDirection {
+    Long,
+    Short,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Buy,
+    Sell,
+}
+
+impl Side {
+    fn flip(self) -> Self {
+        match self {
+            Side::Buy => Side::Sell,
+            Side::Sell => Side::Buy,
+        }
+    }
+
+    fn action(self, intent: PositionIntent) -> Action {
+        match (self, intent) {
+            (Side::Buy, PositionIntent::Open) => Action::BuyToOpen,
+            (Side::Sell, PositionIntent::Open) => Action::SellToOpen,
+            (Side::Buy, PositionIntent::Close) => Action::BuyToClose,
+            (Side::Sell, PositionIntent::Close) => Action::SellToClose,
+        }
+    }
+}
+
+/// Legs and price effect of a multi-leg order, without price or time in force.
+///
+/// Constructors describe the strategy as it is opened; passing
+/// [`PositionIntent::Close`] reverses every leg and the price effect.
+#[derive(Debug, Clone)]
+pub struct Strategy {
+    pub legs: Vec<OrderLeg>,
+    pub price_effect: PriceEffect,
+}
+
+impl Strategy {
+    fn build(
+        legs: Vec<(InstrumentType, Symbol, Decimal, Side)>,
+        opening_effect: PriceEffect,
+        intent: PositionIntent,
+    ) -> Self {
+        let legs = legs
+            .into_iter()
+            .map(|(instrument_type, symbol, quantity, side)| {
+                let side = match intent {
+                    PositionIntent::Open => side,
+                    PositionIntent::Close => side.flip(),
+                };
+                OrderLeg {
+                    instrument_type,
+                    symbol,
+                    quantity: Some(quantity),
+                    action: side.action(intent),
+                }
+            })
+            .collect();
+        let price_effect = match intent {
+            PositionIntent::Open => opening_effect,
+            PositionIntent::Close => opening_effect.opposite(),
+        };
+        Self { legs, price_effect }
+    }
+
+    /// A vertical spread buying `long` and selling `short` in the same expiration.
+    pub fn vertical(
+        option_type: OptionType,
+        long: &Strike,
+        short: &Strike,
+        quantity: Decimal,
+        intent: PositionIntent,
+    ) -> Self {
+        let debit = match option_type {
+            OptionType::Call => long.strike_price < short.strike_price,
+            OptionType::Put => long.strike_price > short.strike_price,
+        };
+        let effect = if debit {
+            PriceEffect::Debit
+        } else {
+            PriceEffect::Credit
+        };
+        Self::build(
+            vec![
+                option_leg(long, option_type, quantity, Side::Buy),
+                option_leg(short, option_type, quantity, Side::Sell),
+            ],
+            effect,
+            intent,
+        )
+    }
+
+    pub fn strangle(
+        put: &Strike,
+        call: &Strike,
+        quantity: Decimal,
+        direction: StrategyDirection,
+        intent: PositionIntent,
+    ) -> Self {
+        let side = direction.side();
+        Self::build(
+            vec![
+                option_leg(put, OptionType::Put, quantity, side),
+                option_leg(call, OptionType::Call, quantity, side),
+            ],
+            direction.effect(),
+            intent,
+        )
+    }
+
+    pub fn straddle(
+        strike: &Strike,
+        quantity: Decimal,
+        direction: StrategyDirection,
+        intent: PositionIntent,
+    ) -> Self {
+        Self::strangle(strike, strike, quantity, direction, intent)
+    }
+
+    /// An iron condor. A short iron condor sells the inner strikes and buys the
+    /// outer wings for a credit; a long one does the reverse for a debit.
+    pub fn iron_condor(
+        outer_put: &Strike,
+        inner_put: &Strike,
+        inner_call: &Strike,
+        outer_call: &Strike,
+        quantity: Decimal,
+        direction: StrategyDirection,
+        intent: PositionIntent,
+    ) -> Self {
+        let inner = direction.side();
+        let outer = inner.flip();
+        Self::build(
+            vec![
+                option_leg(outer_put, OptionType::Put, quantity, outer),
+                option_leg(inner_put, OptionType::Put, quantity, inner),
+                option_leg(inner_call, OptionType::Call, quantity, inner),
+                option_leg(outer_call, OptionType::Call, quantity, outer),
+            ],
+            direction.effect(),
+            intent,
+        )
+    }
+
+    /// A 1-2-1 butterfly. A long butterfly buys the wings and sells the body twice.
+    pub fn butterfly(
+        option_type: OptionType,
+        lower: &Strike,
+        middle: &Strike,
+        upper: &Strike,
+        quantity: Decimal,
+        direction: StrategyDirection,
+        intent: PositionIntent,
+    ) -> Self {
+        let wings = direction.side();
+        let body = wings.flip();
+        Self::build(
+            vec![
+                option_leg(lower, option_type, quantity, wings),
+                option_leg(middle, option_type, quantity * Decimal::TWO, body),
+                option_leg(upper, option_type, quantity, wings),
+            ],
+            direction.effect(),
+            intent,
+        )
+    }
+
+    /// A calendar spread. `near` and `far` should be the same strike taken from two
+    /// different expirations, see [`super::option_chain::Expiration::strike`].
+    /// A long calendar sells the near expiration and buys the far one.
+    pub fn calendar(
+        option_type: OptionType,
+        near: &Strike,
+        far: &Strike,
+        quantity: Decimal,
+        direction: StrategyDirection,
+        intent: PositionIntent,
+    ) -> Self {
+        Self::diagonal(option_type, near, far, quantity, direction, intent)
+    }
+
+    /// Like [`Strategy::calendar`], but `near` and `far` may have different strikes.
+    pub fn diagonal(
+        option_type: OptionType,
+        near: &Strike,
+        far: &Strike,
+        quantity: Decimal,
+        direction: StrategyDirection,
+        intent: PositionIntent,
+    ) -> Self {
+        let far_side = direction.side();
+        Self::build(
+            vec![
+                option_leg(near, option_type, quantity, far_side.flip()),
+                option_leg(far, option_type, quantity, far_side),
+            ],
+            direction.effect(),
+            intent,
+        )
+    }
+
+    /// Buys `quantity * shares_per_contract` shares of the underlying and sells `quantity` calls.
+    pub fn covered_call(
+        chain: &NestedOptionChain,
+        call: &Strike,
+        quantity: Decimal,
+        intent: PositionIntent,
+    ) -> Self {
+        Self::build(
+            vec![
+                equity_leg(chain, quantity),
+                option_leg(call, OptionType::Call, quantity, Side::Sell),
+            ],
+            PriceEffect::Debit,
+            intent,
+        )
+    }
+
+    /// A covered call with an additional protective put.
+    pub fn collar(
+        chain: &NestedOptionChain,
+        put: &Strike,
+        call: &Strike,
+        quantity: Decimal,
+        intent: PositionIntent,
+    ) -> Self {
+        Self::build(
+            vec![
+                equity_leg(chain, quantity),
+                option_leg(put, OptionType::Put, quantity, Side::Buy),
+                option_leg(call, OptionType::Call, quantity, Side::Sell),
+            ],
+            PriceEffect::Debit,
+            intent,
+        )
+    }
+
+    /// Closes a single position. Returns `None` if the position is flat.
+    pub fn close_position(position: &FullPosition) -> Option<Self> {
+        let leg = position.closing_leg()?;
+        let price_effect = match position.quantity_direction {
+            QuantityDirection::Short => PriceEffect::Debit,
+            _ => PriceEffect::Credit,
+        };
+        Some(Self {
+            legs: vec![leg],
+            price_effect,
+        })
+    }
+
+    /// Closes many positions, grouping them into multi-leg orders by underlying.
+    ///
+    /// Each resulting strategy has at most [`MAX_LEGS`] legs. The price effect is
+    /// estimated from each position's close price; flat positions are skipped.
+    pub fn close_positions<'a>(positions: impl IntoIterator<Item = &'a FullPosition>) -> Vec<Self> {
+        let mut by_underlying: BTreeMap<&Symbol, Vec<&FullPosition>> = BTreeMap::new();
+        for position in positions {
+            if position.closing_action().is_some() {
+                by_underlying
+                    .entry(&position.underlying_symbol)
+                    .or_default()
+                    .push(position);
+            }
+        }
+
+        by_underlying
+            .into_values()
+            .flat_map(|positions| {
+                positions
+                    .chunks(MAX_LEGS)
+                    .map(|chunk| {
+                        let proceeds: Decimal = chunk
+                            .iter()
+                            .map(|position| {
+                                let value = position.close_price
+                                    * position.quantity.abs()
+                                    * position.multiplier;
+                                match position.quantity_direction {
+                                    QuantityDirection::Short => -value,
+                                    _ => value,
+                                }
+                            })
+                            .sum();
+                        let price_effect = if proceeds.is_sign_negative() {
+                            PriceEffect::Debit
+                        } else {
+                            PriceEffect::Credit
+                        };
+                        Self {
+                            legs: chunk.iter().filter_map(|p| p.closing_leg()).collect(),
+                            price_effect,
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Closes an equity option position and opens the same strike and type in `target`.
+    ///
+    /// Returns `None` if the position is flat, is not an equity option, or `target`
+    /// has no matching strike. Future options are rolled with
+    /// [`Strategy::roll_future_option`]. Rolling a long position is assumed to be a
+    /// debit and rolling a short position a credit.
+    pub fn roll(position: &FullPosition, target: &Expiration) -> Option<Self> {
+        if !matches!(position.instrument_type, InstrumentType::EquityOption) {
+            return None;
+        }
+        let option = OptionSymbol::try_from(&position.symbol).ok()?;
+        let strike = target.strike(option.strike)?;
+        Self::rolled(position, strike.symbol(option.option_type))
+    }
+
+    /// Closes a future option position and opens the same strike and type in `target`.
+    ///
+    /// Returns `None` if the position is flat, is not a future option, or `target`
+    /// has no matching strike. Debits and credits are assumed as for [`Strategy::roll`].
+    pub fn roll_future_option(
+        position: &FullPosition,
+        target: &FutureOptionExpiration,
+    ) -> Option<Self> {
+        if !matches!(position.instrument_type, InstrumentType::FutureOption) {
+            return None;
+        }
+        let option = FutureOptionSymbol::try_from(&position.symbol).ok()?;
+        let strike = target.strike(option.strike)?;
+        Self::rolled(position, strike.symbol(option.option_type))
+    }
+
+    fn rolled(position: &FullPosition, symbol: &Symbol) -> Option<Self> {
+        let closing = position.closing_leg()?;
+        let (action, price_effect) = match position.quantity_direction {
+            QuantityDirection::Short => (Action::SellToOpen, PriceEffect::Credit),
+            _ => (Action::BuyToOpen, PriceEffect::Debit),
+        };
+        let opening = OrderLeg {
+            instrument_type: position.instrument_type.clone(),
+            symbol: symbol.clone(),
+            quantity: closing.quantity,
+            action,
+        };
+        Some(Self {
+            legs: vec![closing, opening],
+            price_effect,
+        })
+    }
+
+    /// Returns an [`OrderBuilder`] with legs and price effect filled in.
+    pub fn order_builder(&self) -> OrderBuilder {
+        let mut builder = OrderBuilder::default();
+        builder
+            .legs(self.legs.clone())
+            .price_effect(self.price_effect.clone());
+        builder
+    }
+
+    pub fn into_order(
+        self,
+        order_type: OrderType,
+        time_in_force: TimeInForce,
+        price: Decimal,
+    ) -> Order {
+        Order {
+            time_in_force,
+            order_type,
+            price: Some(price),
+            price_effect: Some(self.price_effect),
+            stop_trigger: None,
+            value: None,
+            value_effect: None,
+            external_identifier: None,
+            legs: self.legs,
+        }
+    }
+}
+
+impl StrategyDirection {
+    fn side(self) -> Side {
+        match self {
+            StrategyDirection::Long => Side::Buy,
+            StrategyDirection::Short => Side::Sell,
+        }
+    }
+
+    fn effect(self) -> PriceEffect {
+        match self {
+            StrategyDirection::Long => PriceEffect::Debit,
+            StrategyDirection::Short => PriceEffect::Credit,
+        }
+    }
+}
+
+impl Strike {
+    pub fn symbol(&self, option_type: OptionType) -> &Symbol {
+        match option_type {
+            OptionType::Call => &self.call,
+            OptionType::Put => &self.put,
+        }
+    }
+}
+
+impl FutureOptionStrike {
+    pub fn symbol(&self, option_type: OptionType) -> &Symbol {
+        match option_type {
+            OptionType::Call => &self.call,
+            OptionType::Put => &self.put,
+        }
+    }
+}
+
+fn option_leg(
+    strike: &Strike,
+    option_type: OptionType,
+    quantity: Decimal,
+    side: Side,
+) -> (InstrumentType, Symbol, Decimal, Side) {
+    (
+        InstrumentType::EquityOption,
+        strike.symbol(option_type).clone(),
+        quantity,
+        side,
+    )
+}
+
+fn equity_leg(
+    chain: &NestedOptionChain,
+    quantity: Decimal,
+) -> (InstrumentType, Symbol, Decimal, Side) {
+    (
+        InstrumentType::Equity,
+        chain.underlying_symbol.clone(),
+        quantity * Decimal::from(chain.shares_per_contract),
+        Side::Buy,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn strike(price: i64) -> Strike {
+        Strike {
+            strike_price: Decimal::from(price),
+            call: format!("SPY   240119C{:05}000", price).into(),
+            put: format!("SPY   240119P{:05}000", price).into(),
+        }
+    }
+
+    fn actions(strategy: &Strategy) -> Vec<Action> {
+        strategy.legs.iter().map(|leg| leg.action.clone()).collect()
+    }
+
+    fn symbols(strategy: &Strategy) -> Vec<&str> {
+        strategy
+            .legs
+            .iter()
+            .map(|leg| leg.symbol.0.as_str())
+            .collect()
+    }
+
+    fn chain() -> NestedOptionChain {
+        NestedOptionChain {
+            underlying_symbol: "SPY".into(),
+            root_symbol: "SPY".into(),
+            option_chain_type: "Standard".into(),
+            shares_per_contract: 100,
+            expirations: vec![],
+        }
+    }
+
+    #[test]
+    fn iron_condor_direction() {
+        let strikes = [strike(400), strike(410), strike(450), strike(460)];
+        let condor = |direction, intent| {
+            let [outer_put, inner_put, inner_call, outer_call] = &strikes;
+            Strategy::iron_condor(
+                outer_put,
+                inner_put,
+                inner_call,
+                outer_call,
+                Decimal::ONE,
+                direction,
+                intent,
+            )
+        };
+
+        let short = condor(StrategyDirection::Short, PositionIntent::Open);
+        assert_eq!(short.price_effect, PriceEffect::Credit);
+        assert_eq!(
+            actions(&short),
+            [
+                Action::BuyToOpen,
+                Action::SellToOpen,
+                Action::SellToOpen,
+                Action::BuyToOpen
+            ]
+        );
+        assert_eq!(
+            symbols(&short),
+            [
+                "SPY   240119P00400000",
+                "SPY   240119P00410000",
+                "SPY   240119C00450000",
+                "SPY   240119C00460000",
+            ]
+        );
+
+        let long = condor(StrategyDirection::Long, PositionIntent::Open);
+        assert_eq!(long.price_effect, PriceEffect::Debit);
+        assert_eq!(
+            actions(&long),
+            [
+                Action::SellToOpen,
+                Action::BuyToOpen,
+                Action::BuyToOpen,
+                Action::SellToOpen
+            ]
+        );
+
+        let closing = condor(StrategyDirection::Short, PositionIntent::Close);
+        assert_eq!(closing.price_effect, PriceEffect::Debit);
+        assert_eq!(
+            actions(&closing),
+            [
+                Action::SellToClose,
+                Action::BuyToClose,
+                Action::BuyToClose,
+                Action::SellToClose
+            ]
+        );
+    }
+
+    #[test]
+    fn vertical_effect_follows_strikes() {
+        let (low, high) = (strike(400), strike(410));
+        let vertical = |option_type, long, short| {
+            Strategy::vertical(option_type, long, short, Decimal::ONE, PositionIntent::Open)
+                .price_effect
+        };
+        assert_eq!(vertical(OptionType::Call, &low, &high), PriceEffect::Debit);
+        assert_eq!(vertical(OptionType::Call, &high, &low), PriceEffect::Credit);
+        assert_eq!(vertical(OptionType::Put, &high, &low), PriceEffect::Debit);
+        assert_eq!(vertical(OptionType::Put, &low, &high), PriceEffect::Credit);
+    }
+
+    #[test]
+    fn butterfly_sells_body_twice() {
+        let strikes = [strike(400), strike(410), strike(420)];
+        let butterfly = Strategy::butterfly(
+            OptionType::Call,
+            &strikes[0],
+            &strikes[1],
+            &strikes[2],
+            Decimal::from(3),
+            StrategyDirection::Long,
+            PositionIntent::Open,
+        );
+        let quantities: Vec<_> = butterfly.legs.iter().map(|leg| leg.quantity).collect();
+        assert_eq!(
+            quantities,
+            [
+                Some(Decimal::from(3)),
+                Some(Decimal::from(6)),
+                Some(Decimal::from(3))
+            ]
+        );
+        assert_eq!(
+            actions(&butterfly),
+            [Action::BuyToOpen, Action::SellToOpen, Action::BuyToOpen]
+        );
+    }
+
+    #[test]
+    fn collar_buys_shares_and_put() {
+        let (put, call) = (strike(400), strike(450));
+        let collar = Strategy::collar(&chain(), &put, &call, Decimal::TWO, PositionIntent::Open);
+        assert_eq!(collar.price_effect, PriceEffect::Debit);
+        let shares = &collar.legs[0];
+        assert_eq!(shares.instrument_type, InstrumentType::Equity);
+        assert_eq!(shares.symbol, Symbol::from("SPY"));
+        assert_eq!(shares.quantity, Some(Decimal::from(200)));
+        assert_eq!(
+            actions(&collar)[1..],
+            [Action::BuyToOpen, Action::SellToOpen]
+        );
+        assert_eq!(
+            symbols(&collar)[1..],
+            ["SPY   240119P00400000", "SPY   240119C00450000"]
+        );
+
+        let closing = Strategy::collar(&chain(), &put, &call, Decimal::TWO, PositionIntent::Close);
+        assert_eq!(closing.price_effect, PriceEffect::Credit);
+        assert_eq!(
+            actions(&closing),
+            [Action::SellToClose, Action::SellToClose, Action::BuyToClose]
+        );
+    }
+
+    #[test]
+    fn roll_keeps_strike_and_type() {
+        let target = Expiration {
+            expiration_type: "Regular".into(),
+            expiration_date: "2024-02-16".into(),
+            days_to_expiration: 60,
+            settlement_type: "PM".into(),
+            strikes: vec![Strike {
+                strike_price: Decimal::from(450),
+                call: "SPY   240216C00450000".into(),
+                put: "SPY   240216P00450000".into(),
+            }],
+        };
+
+        let short = FullPosition::test(InstrumentType::EquityOption, "SPY   240119P00450000", -2);
+        let roll = Strategy::roll(&short, &target).unwrap();
+        assert_eq!(roll.price_effect, PriceEffect::Credit);
+        assert_eq!(actions(&roll), [Action::BuyToClose, Action::SellToOpen]);
+        assert_eq!(
+            symbols(&roll),
+            ["SPY   240119P00450000", "SPY   240216P00450000"]
+        );
+        assert_eq!(roll.legs[1].quantity, Some(Decimal::TWO));
+
+        let long = FullPosition::test(InstrumentType::EquityOption, "SPY   240119C00450000", 1);
+        let roll = Strategy::roll(&long, &target).unwrap();
+        assert_eq!(roll.price_effect, PriceEffect::Debit);
+        assert_eq!(actions(&roll), [Action::SellToClose, Action::BuyToOpen]);
+        assert_eq!(symbols(&roll)[1], "SPY   240216C00450000");
+
+        let other_strike =
+            FullPosition::test(InstrumentType::EquityOption, "SPY   240119C00460000", 1);
+        assert!(Strategy::roll(&other_strike, &target).is_none());
+        let flat = FullPosition::test(InstrumentType::EquityOption, "SPY   240119C00450000", 0);
+        assert!(Strategy::roll(&flat, &target).is_none());
+        let stock = FullPosition::test(InstrumentType::Equity, "SPY", 100);
+        assert!(Strategy::roll(&stock, &target).is_none());
+    }
+
+    #[test]
+    fn roll_future_option_keeps_strike_and_type() {
+        let target: FutureOptionExpiration = serde_json::from_value(json!({
+            "underlying-symbol": "/ESZ3",
+            "root-symbol": "/ES",
+            "option-root-symbol": "EW4",
+            "option-contract-symbol": "EW4V3",
+            "asset": "ES",
+            "expiration-date": "2023-10-27",
+            "days-to-expiration": 30,
+            "expiration-type": "Weekly",
+            "settlement-type": "PM",
+            "notional-value": "0.5",
+            "display-factor": "0.01",
+            "strike-factor": "1.0",
+            "stops-trading-at": "2023-10-27T20:00:00.000+00:00",
+            "expires-at": "2023-10-27T20:00:00.000+00:00",
+            "strikes": [{
+                "strike-price": "4000.0",
+                "call": "./ESZ3 EW4V3 231027C4000",
+                "put": "./ESZ3 EW4V3 231027P4000",
+                "call-streamer-symbol": "./EW4V23C4000:XCME",
+                "put-streamer-symbol": "./EW4V23P4000:XCME",
+            }],
+        }))
+        .unwrap();
+
+        let short =
+            FullPosition::test(InstrumentType::FutureOption, "./ESZ3 EW4U3 230929P4000", -1);
+        let roll = Strategy::roll_future_option(&short, &target).unwrap();
+        assert_eq!(roll.price_effect, PriceEffect::Credit);
+        assert_eq!(actions(&roll), [Action::BuyToClose, Action::SellToOpen]);
+        assert_eq!(symbols(&roll)[1], "./ESZ3 EW4V3 231027P4000");
+        assert_eq!(roll.legs[1].instrument_type, InstrumentType::FutureOption);
+
+        let equity_option =
+            FullPosition::test(InstrumentType::EquityOption, "SPY   240119P00450000", -1);
+        assert!(Strategy::roll_future_option(&equity_option, &target).is_none());
+    }
+}
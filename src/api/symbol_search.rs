@@ -0,0 +1,79 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::client::path_segment;
+use crate::{Result, TastyTrade};
+
+use super::base::Items;
+use super::order::{InstrumentType, Symbol};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct SymbolSearchResult {
+    pub symbol: Symbol,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub instrument_type: Option<InstrumentType>,
+    #[serde(default)]
+    pub listed_market: Option<String>,
+    /// Whether options are listed on the symbol.
+    #[serde(default)]
+    pub options: bool,
+    #[serde(default)]
+    pub price_increments: Option<String>,
+    #[serde(default)]
+    pub trading_hours: Option<String>,
+}
+
+impl TastyTrade {
+    /// Symbols starting with `prefix`. An empty prefix matches nothing.
+    pub async fn search_symbols(&self, prefix: &str) -> Result<Vec<SymbolSearchResult>> {
+        let prefix = prefix.trim();
+        if prefix.is_empty() {
+            return Ok(vec![]);
+        }
+        let resp: Items<SymbolSearchResult> = self
+            .get(format!("/symbols/search/{}", path_segment(prefix)))
+            .await?;
+        Ok(resp.items)
+    }
+}
+
+/// Type-ahead search that only queries the API once input settles.
+///
+/// Each call to [`SymbolSearch::search`] waits for the debounce period and
+/// returns `None` if a newer call was made in the meantime, so only the latest
+/// keystroke hits the API. Dropping a pending search cancels it without
+/// affecting the others.
+#[derive(Debug)]
+pub struct SymbolSearch {
+    tasty: TastyTrade,
+    debounce: Duration,
+    latest: AtomicU64,
+}
+
+impl SymbolSearch {
+    pub fn new(tasty: &TastyTrade, debounce: Duration) -> Self {
+        Self {
+            tasty: tasty.clone(),
+            debounce,
+            latest: AtomicU64::new(0),
+        }
+    }
+
+    /// Searches for `prefix` unless superseded before or while the request is made.
+    pub async fn search(&self, prefix: &str) -> Result<Option<Vec<SymbolSearchResult>>> {
+        let generation = self.latest.fetch_add(1, Ordering::SeqCst) + 1;
+        let is_latest = || self.latest.load(Ordering::SeqCst) == generation;
+
+        tokio::time::sleep(self.debounce).await;
+        if !is_latest() {
+            return Ok(None);
+        }
+        let results = self.tasty.search_symbols(prefix).await?;
+        Ok(is_latest().then_some(results))
+    }
+}
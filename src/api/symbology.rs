@@ -0,0 +1,649 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Datelike, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::order::{AsSymbol, InstrumentType, OrderLegBuilder, Symbol};
+use super::quote_streaming::DxFeedSymbol;
+use super::strategy::OptionType;
+
+const OCC_ROOT_LEN: usize = 6;
+const OCC_STRIKE_DIGITS: usize = 8;
+const OCC_STRIKE_SCALE: u32 = 3;
+
+/// Implements conversions to and from [`Symbol`], [`AsSymbol`] and serde for
+/// a symbol type in terms of its `Display` and `FromStr` impls.
+macro_rules! symbol_conversions {
+    ($name:ident) => {
+        impl TryFrom<&Symbol> for $name {
+            type Error = InvalidSymbol;
+
+            fn try_from(symbol: &Symbol) -> Result<Self, Self::Error> {
+                symbol.0.parse()
+            }
+        }
+
+        impl From<$name> for Symbol {
+            fn from(symbol: $name) -> Self {
+                Symbol(symbol.to_string())
+            }
+        }
+
+        impl From<&$name> for Symbol {
+            fn from(symbol: &$name) -> Self {
+                Symbol(symbol.to_string())
+            }
+        }
+
+        impl AsSymbol for $name {
+            fn as_symbol(&self) -> Symbol {
+                self.into()
+            }
+        }
+
+        impl AsSymbol for &$name {
+            fn as_symbol(&self) -> Symbol {
+                (*self).into()
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let symbol = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+                symbol.parse().map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("Invalid symbol {0:?}")]
+pub struct InvalidSymbol(pub String);
+
+/// An equity option symbol in the OCC format, e.g. `SPXW  230623P04295000`:
+/// the root padded to six characters, the expiration as `YYMMDD`, `C` or `P`,
+/// and the strike times 1000 padded to eight digits.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OptionSymbol {
+    pub root: String,
+    pub expiration: NaiveDate,
+    pub option_type: OptionType,
+    pub strike: Decimal,
+}
+
+impl OptionSymbol {
+    pub fn new(
+        root: impl Into<String>,
+        expiration: NaiveDate,
+        option_type: OptionType,
+        strike: Decimal,
+    ) -> Self {
+        Self {
+            root: root.into(),
+            expiration,
+            option_type,
+            strike: strike.normalize(),
+        }
+    }
+
+    pub fn parse_occ(symbol: &str) -> Result<Self, InvalidSymbol> {
+        let invalid = || InvalidSymbol(symbol.to_owned());
+        let split = symbol
+            .len()
+            .checked_sub(7 + OCC_STRIKE_DIGITS)
+            .ok_or_else(invalid)?;
+        let root = symbol.get(..split).ok_or_else(invalid)?.trim_end();
+        let rest = symbol.get(split..).ok_or_else(invalid)?;
+        if root.is_empty() || root.len() > OCC_ROOT_LEN {
+            return Err(invalid());
+        }
+        let strike = rest.get(7..).ok_or_else(invalid)?;
+        if !strike.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let strike = strike.parse::<i64>().map_err(|_| invalid())?;
+        Ok(Self::new(
+            root,
+            parse_date(rest.get(..6).ok_or_else(invalid)?).ok_or_else(invalid)?,
+            rest.chars()
+                .nth(6)
+                .and_then(parse_option_type)
+                .ok_or_else(invalid)?,
+            Decimal::new(strike, OCC_STRIKE_SCALE),
+        ))
+    }
+
+    /// Formats the symbol as OCC. Strikes with more than three decimal places
+    /// are truncated.
+    pub fn to_occ(&self) -> String {
+        let strike = (self.strike * Decimal::from(10u32.pow(OCC_STRIKE_SCALE)))
+            .trunc()
+            .to_string();
+        format!(
+            "{:<root_width$}{}{}{:0>strike_width$}",
+            self.root,
+            self.expiration.format("%y%m%d"),
+            option_type_char(self.option_type),
+            strike,
+            root_width = OCC_ROOT_LEN,
+            strike_width = OCC_STRIKE_DIGITS,
+        )
+    }
+
+    /// Parses a dxFeed option symbol such as `.SPXW230623P4295`.
+    pub fn from_streamer_symbol(symbol: &str) -> Result<Self, InvalidSymbol> {
+        let invalid = || InvalidSymbol(symbol.to_owned());
+        let body = symbol.strip_prefix('.').ok_or_else(invalid)?;
+        // Roots may contain digits and letters, so look for the last C or P
+        // that is followed by a strike and preceded by a date.
+        let (index, option_type, strike) = body
+            .char_indices()
+            .rev()
+            .filter_map(|(index, c)| {
+                let option_type = parse_option_type(c)?;
+                let strike = Decimal::from_str_exact(body.get(index + 1..)?).ok()?;
+                Some((index, option_type, strike))
+            })
+            .next()
+            .ok_or_else(invalid)?;
+        let date_start = index.checked_sub(6).ok_or_else(invalid)?;
+        let root = body.get(..date_start).ok_or_else(invalid)?;
+        if root.is_empty() {
+            return Err(invalid());
+        }
+        let expiration =
+            parse_date(body.get(date_start..index).ok_or_else(invalid)?).ok_or_else(invalid)?;
+        Ok(Self::new(root, expiration, option_type, strike))
+    }
+
+    pub fn to_streamer_symbol(&self) -> DxFeedSymbol {
+        DxFeedSymbol(format!(
+            ".{}{}{}{}",
+            self.root,
+            self.expiration.format("%y%m%d"),
+            option_type_char(self.option_type),
+            self.strike.normalize(),
+        ))
+    }
+}
+
+fn parse_date(date: &str) -> Option<NaiveDate> {
+    if date.len() != 6 || !date.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    NaiveDate::parse_from_str(date, "%y%m%d").ok()
+}
+
+fn parse_option_type(option_type: char) -> Option<OptionType> {
+    match option_type {
+        'C' => Some(OptionType::Call),
+        'P' => Some(OptionType::Put),
+        _ => None,
+    }
+}
+
+fn option_type_char(option_type: OptionType) -> char {
+    match option_type {
+        OptionType::Call => 'C',
+        OptionType::Put => 'P',
+    }
+}
+
+impl fmt::Display for OptionSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_occ())
+    }
+}
+
+impl FromStr for OptionSymbol {
+    type Err = InvalidSymbol;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_occ(s)
+    }
+}
+
+impl TryFrom<&DxFeedSymbol> for OptionSymbol {
+    type Error = InvalidSymbol;
+
+    fn try_from(symbol: &DxFeedSymbol) -> Result<Self, Self::Error> {
+        Self::from_streamer_symbol(&symbol.0)
+    }
+}
+
+symbol_conversions!(OptionSymbol);
+
+impl OrderLegBuilder {
+    /// Sets the leg's symbol to an equity option.
+    pub fn option_symbol(&mut self, symbol: &OptionSymbol) -> &mut Self {
+        self.instrument_type(InstrumentType::EquityOption)
+            .symbol(symbol)
+    }
+}
+
+/// Futures month codes, January to December.
+const MONTH_CODES: [char; 12] = ['F', 'G', 'H', 'J', 'K', 'M', 'N', 'Q', 'U', 'V', 'X', 'Z'];
+
+/// The futures month code of `month` (1 to 12), e.g. `Z` for December.
+pub fn month_code(month: u32) -> Option<char> {
+    MONTH_CODES.get(month.checked_sub(1)? as usize).copied()
+}
+
+/// The month (1 to 12) of a futures month code.
+pub fn month_from_code(code: char) -> Option<u32> {
+    let index = MONTH_CODES.iter().position(|c| *c == code)?;
+    Some(index as u32 + 1)
+}
+
+/// A contract code such as `ESZ3`: product code, month code and year.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ContractCode {
+    product_code: String,
+    month: u32,
+    year: i32,
+}
+
+impl ContractCode {
+    /// Parses a code ending in a one or two digit year, resolved relative to `reference_year`.
+    fn parse(code: &str, reference_year: i32) -> Option<Self> {
+        let digits = code.len() - code.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+        if !(1..=2).contains(&digits) {
+            return None;
+        }
+        let (head, year) = code.split_at(code.len() - digits);
+        let mut chars = head.chars();
+        let month = month_from_code(chars.next_back()?)?;
+        let product_code = chars.as_str();
+        if product_code.is_empty() || !product_code.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return None;
+        }
+        let year: i32 = year.parse().ok()?;
+        let period = 10_i32.pow(digits as u32);
+        // One digit years cover a decade, so only allow recently expired
+        // contracts; two digit years are taken as the nearest.
+        let past = if digits == 1 { 2 } else { period / 2 };
+        let earliest = reference_year - past;
+        let year = earliest + (year - earliest).rem_euclid(period);
+        Some(Self {
+            product_code: product_code.to_owned(),
+            month,
+            year,
+        })
+    }
+
+    fn month_code(&self) -> char {
+        month_code(self.month).unwrap_or('?')
+    }
+
+    /// The code with a one digit year, as used by tastytrade.
+    fn short(&self) -> String {
+        format!(
+            "{}{}{}",
+            self.product_code,
+            self.month_code(),
+            self.year.rem_euclid(10)
+        )
+    }
+
+    /// The code with a two digit year, as used by dxFeed.
+    fn long(&self) -> String {
+        format!(
+            "{}{}{:02}",
+            self.product_code,
+            self.month_code(),
+            self.year.rem_euclid(100)
+        )
+    }
+}
+
+fn current_year() -> i32 {
+    Utc::now().year()
+}
+
+/// A futures symbol such as `/ESZ3`.
+///
+/// tastytrade writes the year with one digit, which is resolved to the
+/// nearest matching year no more than two years in the past.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FutureSymbol {
+    pub product_code: String,
+    /// Contract month, 1 to 12.
+    pub month: u32,
+    pub year: i32,
+}
+
+impl FutureSymbol {
+    pub fn new(product_code: impl Into<String>, month: u32, year: i32) -> Self {
+        Self {
+            product_code: product_code.into(),
+            month,
+            year,
+        }
+    }
+
+    /// Parses a symbol, resolving its year relative to `reference_year` instead of the current year.
+    pub fn parse_as_of(symbol: &str, reference_year: i32) -> Result<Self, InvalidSymbol> {
+        let code = symbol
+            .strip_prefix('/')
+            .and_then(|code| ContractCode::parse(code, reference_year))
+            .ok_or_else(|| InvalidSymbol(symbol.to_owned()))?;
+        Ok(code.into())
+    }
+
+    /// Parses a dxFeed futures symbol such as `/ESZ23:XCME`, dropping the exchange.
+    pub fn from_streamer_symbol(symbol: &str) -> Result<Self, InvalidSymbol> {
+        let (symbol, _exchange) = symbol.split_once(':').unwrap_or((symbol, ""));
+        Self::parse_as_of(symbol, current_year())
+    }
+
+    /// The dxFeed symbol on `exchange`, e.g. `/ESZ23:XCME` for `XCME`.
+    pub fn to_streamer_symbol(&self, exchange: &str) -> DxFeedSymbol {
+        DxFeedSymbol(format!("/{}:{exchange}", self.code().long()))
+    }
+
+    pub fn month_code(&self) -> char {
+        self.code().month_code()
+    }
+
+    fn code(&self) -> ContractCode {
+        ContractCode {
+            product_code: self.product_code.clone(),
+            month: self.month,
+            year: self.year,
+        }
+    }
+}
+
+impl From<ContractCode> for FutureSymbol {
+    fn from(code: ContractCode) -> Self {
+        Self::new(code.product_code, code.month, code.year)
+    }
+}
+
+impl fmt::Display for FutureSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "/{}", self.code().short())
+    }
+}
+
+impl FromStr for FutureSymbol {
+    type Err = InvalidSymbol;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_as_of(s, current_year())
+    }
+}
+
+symbol_conversions!(FutureSymbol);
+
+/// A futures option symbol such as `./ESZ3 EW4U3 230929P4000`: the underlying
+/// future, the option contract code, the expiration as `YYMMDD`, `C` or `P`,
+/// and the strike.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FutureOptionSymbol {
+    pub underlying: FutureSymbol,
+    /// Option product code, e.g. `EW4` for the fourth-week Friday options on `ES`.
+    pub option_product_code: String,
+    /// Contract month of the option, 1 to 12.
+    pub option_month: u32,
+    pub option_year: i32,
+    pub expiration: NaiveDate,
+    pub option_type: OptionType,
+    pub strike: Decimal,
+}
+
+impl FutureOptionSymbol {
+    /// Parses a symbol, resolving years relative to `reference_year` instead of the current year.
+    pub fn parse_as_of(symbol: &str, reference_year: i32) -> Result<Self, InvalidSymbol> {
+        let invalid = || InvalidSymbol(symbol.to_owned());
+        let body = symbol.strip_prefix('.').ok_or_else(invalid)?;
+        let mut parts = body.split_whitespace();
+        let (Some(underlying), Some(option_code), Some(contract), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        let underlying = FutureSymbol::parse_as_of(underlying, reference_year)?;
+        let option_code = ContractCode::parse(option_code, reference_year).ok_or_else(invalid)?;
+        let expiration = parse_date(contract.get(..6).ok_or_else(invalid)?).ok_or_else(invalid)?;
+        let option_type = contract
+            .chars()
+            .nth(6)
+            .and_then(parse_option_type)
+            .ok_or_else(invalid)?;
+        let strike = contract
+            .get(7..)
+            .and_then(|strike| Decimal::from_str_exact(strike).ok())
+            .ok_or_else(invalid)?;
+        Ok(Self {
+            underlying,
+            option_product_code: option_code.product_code,
+            option_month: option_code.month,
+            option_year: option_code.year,
+            expiration,
+            option_type,
+            strike: strike.normalize(),
+        })
+    }
+
+    /// The dxFeed symbol on `exchange`, e.g. `./EW4U23P4000:XCME` for `XCME`.
+    pub fn to_streamer_symbol(&self, exchange: &str) -> DxFeedSymbol {
+        DxFeedSymbol(format!(
+            "./{}{}{}:{exchange}",
+            self.option_code().long(),
+            option_type_char(self.option_type),
+            self.strike.normalize()
+        ))
+    }
+
+    fn option_code(&self) -> ContractCode {
+        ContractCode {
+            product_code: self.option_product_code.clone(),
+            month: self.option_month,
+            year: self.option_year,
+        }
+    }
+}
+
+impl fmt::Display for FutureOptionSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            ".{} {} {}{}{}",
+            self.underlying,
+            self.option_code().short(),
+            self.expiration.format("%y%m%d"),
+            option_type_char(self.option_type),
+            self.strike.normalize()
+        )
+    }
+}
+
+impl FromStr for FutureOptionSymbol {
+    type Err = InvalidSymbol;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_as_of(s, current_year())
+    }
+}
+
+symbol_conversions!(FutureOptionSymbol);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn parses_occ_symbol() {
+        let symbol = OptionSymbol::parse_occ("SPXW  230623P04295000").unwrap();
+        assert_eq!(
+            symbol,
+            OptionSymbol::new(
+                "SPXW",
+                date(2023, 6, 23),
+                OptionType::Put,
+                Decimal::from(4295)
+            )
+        );
+        assert_eq!(symbol.to_occ(), "SPXW  230623P04295000");
+    }
+
+    #[test]
+    fn converts_occ_to_streamer_symbol() {
+        let symbol = OptionSymbol::parse_occ("SPXW  230623P04295000").unwrap();
+        assert_eq!(symbol.to_streamer_symbol().0, ".SPXW230623P4295");
+        assert_eq!(
+            OptionSymbol::from_streamer_symbol(".SPXW230623P4295").unwrap(),
+            symbol
+        );
+    }
+
+    #[test]
+    fn round_trips_option_symbols() {
+        for occ in [
+            "SPXW  230623P04295000",
+            "AAPL1 230616C00182500",
+            "C     240119C00050000",
+            "BRKB  231215P00350125",
+        ] {
+            let symbol: OptionSymbol = occ.parse().unwrap();
+            assert_eq!(symbol.to_string(), occ);
+            let streamer = symbol.to_streamer_symbol();
+            assert_eq!(OptionSymbol::try_from(&streamer).unwrap(), symbol);
+        }
+    }
+
+    #[test]
+    fn finds_last_option_type_in_streamer_symbol() {
+        let call = OptionSymbol::from_streamer_symbol(".C240119C50").unwrap();
+        assert_eq!(call.root, "C");
+        assert_eq!(call.option_type, OptionType::Call);
+
+        let put = OptionSymbol::from_streamer_symbol(".PCG230616P12.5").unwrap();
+        assert_eq!(put.root, "PCG");
+        assert_eq!(put.option_type, OptionType::Put);
+        assert_eq!(put.strike, Decimal::new(125, 1));
+
+        let digits = OptionSymbol::from_streamer_symbol(".AAPL1230616C182.5").unwrap();
+        assert_eq!(digits.root, "AAPL1");
+        assert_eq!(digits.expiration, date(2023, 6, 16));
+    }
+
+    #[test]
+    fn rejects_invalid_option_symbols() {
+        assert!(OptionSymbol::parse_occ("AAPL").is_err());
+        assert!(OptionSymbol::parse_occ("SPXW  230623X04295000").is_err());
+        assert!(OptionSymbol::parse_occ("SPXW  231323P04295000").is_err());
+        assert!(OptionSymbol::from_streamer_symbol("SPXW230623P4295").is_err());
+        assert!(OptionSymbol::from_streamer_symbol(".230623P4295").is_err());
+    }
+
+    #[test]
+    fn parses_future_symbol() {
+        let future = FutureSymbol::parse_as_of("/ESZ3", 2023).unwrap();
+        assert_eq!(future, FutureSymbol::new("ES", 12, 2023));
+        assert_eq!(future.month_code(), 'Z');
+        assert_eq!(future.to_string(), "/ESZ3");
+        assert_eq!(future.to_streamer_symbol("XCME").0, "/ESZ23:XCME");
+        assert_eq!(
+            FutureSymbol::from_streamer_symbol("/ESZ23:XCME").unwrap(),
+            future
+        );
+        assert_eq!(
+            FutureSymbol::parse_as_of("/10YZ3", 2023)
+                .unwrap()
+                .product_code,
+            "10Y"
+        );
+    }
+
+    #[test]
+    fn resolves_one_digit_years_near_reference_year() {
+        let year = |symbol: &str, reference_year| {
+            FutureSymbol::parse_as_of(symbol, reference_year)
+                .unwrap()
+                .year
+        };
+        assert_eq!(year("/ESZ3", 2023), 2023);
+        assert_eq!(year("/ESZ4", 2023), 2024);
+        // Up to two years back is taken as recently expired...
+        assert_eq!(year("/ESZ1", 2023), 2021);
+        // ...anything older wraps into the next decade.
+        assert_eq!(year("/ESZ0", 2023), 2030);
+        assert_eq!(year("/6EH4", 2029), 2034);
+        assert_eq!(year("/ESZ9", 2030), 2029);
+    }
+
+    #[test]
+    fn resolves_two_digit_years_to_nearest() {
+        let year =
+            |code: &str, reference_year| ContractCode::parse(code, reference_year).unwrap().year;
+        assert_eq!(year("ESZ23", 2023), 2023);
+        assert_eq!(year("ESZ99", 2001), 1999);
+        assert_eq!(year("ESZ05", 2098), 2105);
+    }
+
+    #[test]
+    fn parses_future_option_symbol() {
+        let option = FutureOptionSymbol::parse_as_of("./ESZ3 EW4U3 230929P4000", 2023).unwrap();
+        assert_eq!(option.underlying, FutureSymbol::new("ES", 12, 2023));
+        assert_eq!(option.option_product_code, "EW4");
+        assert_eq!(option.option_month, 9);
+        assert_eq!(option.option_year, 2023);
+        assert_eq!(option.expiration, date(2023, 9, 29));
+        assert_eq!(option.option_type, OptionType::Put);
+        assert_eq!(option.strike, Decimal::from(4000));
+        assert_eq!(option.to_streamer_symbol("XCME").0, "./EW4U23P4000:XCME");
+    }
+
+    #[test]
+    fn round_trips_future_symbols() {
+        for symbol in ["/ESZ3", "/6EH4", "/CLF5", "/ZBM3"] {
+            let future = FutureSymbol::parse_as_of(symbol, 2023).unwrap();
+            assert_eq!(future.to_string(), symbol);
+        }
+        for symbol in [
+            "./ESZ3 EW4U3 230929P4000",
+            "./CLZ3 LOZ3 231116C85.5",
+            "./ZBZ3 OZBZ3 231124C120",
+        ] {
+            let option = FutureOptionSymbol::parse_as_of(symbol, 2023).unwrap();
+            assert_eq!(option.to_string(), symbol);
+            assert_eq!(Symbol::from(&option).0, symbol);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_future_symbols() {
+        assert!(FutureSymbol::parse_as_of("ESZ3", 2023).is_err());
+        assert!(FutureSymbol::parse_as_of("/ES", 2023).is_err());
+        assert!(FutureSymbol::parse_as_of("/ESA3", 2023).is_err());
+        assert!(FutureSymbol::parse_as_of("/ESZ323", 2023).is_err());
+        assert!(FutureSymbol::parse_as_of("./ESZ3 EW4U3 230929P4000", 2023).is_err());
+        assert!(FutureOptionSymbol::parse_as_of("/ESZ3 EW4U3 230929P4000", 2023).is_err());
+        assert!(FutureOptionSymbol::parse_as_of("./ESZ3 EW4U3", 2023).is_err());
+        assert!(FutureOptionSymbol::parse_as_of("./ESZ3 EW4U3 230929X4000", 2023).is_err());
+    }
+
+    #[test]
+    fn maps_month_codes() {
+        assert_eq!(month_code(1), Some('F'));
+        assert_eq!(month_code(9), Some('U'));
+        assert_eq!(month_code(13), None);
+        assert_eq!(month_code(0), None);
+        assert_eq!(month_from_code('Z'), Some(12));
+        assert_eq!(month_from_code('A'), None);
+    }
+}
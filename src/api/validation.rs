@@ -0,0 +1,617 @@
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use crate::accounts::TradingStatus;
+
+use super::instrument::TickSize;
+use super::order::{
+    Action, InstrumentType, Order, OrderLeg, OrderSide, OrderType, PriceEffect, Symbol,
+    TimeInForce, MAX_LEGS,
+};
+use super::position::{FullPosition, QuantityDirection};
+use super::symbology::OptionSymbol;
+
+/// A reason an order would be rejected, found without contacting the API.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    #[error("Order has no legs")]
+    NoLegs,
+    #[error("Order has {count} legs, at most {max} are allowed")]
+    TooManyLegs { count: usize, max: usize },
+    #[error("Leg {symbol} has non-positive quantity {quantity}")]
+    NonPositiveQuantity { symbol: Symbol, quantity: Decimal },
+    #[error("Leg {symbol} needs a quantity")]
+    MissingQuantity { symbol: Symbol },
+    #[error("Leg {symbol} of a notional order must not have a quantity")]
+    UnexpectedQuantity { symbol: Symbol },
+    #[error("Notional market orders need a value and value effect")]
+    MissingValue,
+    #[error("{0} orders need a price")]
+    MissingPrice(OrderType),
+    #[error("{0} orders must not have a price")]
+    UnexpectedPrice(OrderType),
+    #[error("Price given without a price effect")]
+    MissingPriceEffect,
+    #[error("Price effect is {actual}, but the legs imply {expected}")]
+    PriceEffectMismatch {
+        expected: PriceEffect,
+        actual: PriceEffect,
+    },
+    #[error("{0} orders need a stop trigger")]
+    MissingStopTrigger(OrderType),
+    #[error("Price {price} is not a multiple of the tick size {tick}")]
+    PriceNotOnTick { price: Decimal, tick: Decimal },
+    #[error("{action} {symbol} without a matching position")]
+    ClosingWithoutPosition { symbol: Symbol, action: Action },
+    #[error("{action} {quantity} {symbol} exceeds the position of {available}")]
+    ClosingExceedsPosition {
+        symbol: Symbol,
+        action: Action,
+        quantity: Decimal,
+        available: Decimal,
+    },
+    #[error("Time in force {time_in_force} is not available for {instrument_type}")]
+    TimeInForceNotAllowed {
+        time_in_force: TimeInForce,
+        instrument_type: InstrumentType,
+    },
+    #[error("Account is closed")]
+    AccountClosed,
+    #[error("Account is frozen")]
+    AccountFrozen,
+    #[error("Account may only close {0} positions")]
+    ClosingOnly(InstrumentType),
+    #[error("Account is not enabled for {0}")]
+    InstrumentNotEnabled(InstrumentType),
+}
+
+/// What [`Order::validate`] checks against. Checks whose data is missing are skipped.
+#[derive(Debug, Default, Clone)]
+pub struct ValidationContext<'a> {
+    tick_sizes: HashMap<Symbol, &'a [TickSize]>,
+    option_tick_sizes: HashMap<Symbol, &'a [TickSize]>,
+    positions: Option<&'a [FullPosition]>,
+    trading_status: Option<&'a TradingStatus>,
+}
+
+impl<'a> ValidationContext<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tick sizes for orders on `symbol` itself, e.g. an equity's `tick_sizes`.
+    /// Never used for its options.
+    pub fn with_tick_sizes(mut self, symbol: impl Into<Symbol>, ticks: &'a [TickSize]) -> Self {
+        self.tick_sizes.insert(symbol.into(), ticks);
+        self
+    }
+
+    /// Tick sizes for equity option legs, keyed by option symbol or by root
+    /// symbol, so an equity's `option_tick_sizes` can be given once per root.
+    pub fn with_option_tick_sizes(
+        mut self,
+        symbol: impl Into<Symbol>,
+        ticks: &'a [TickSize],
+    ) -> Self {
+        self.option_tick_sizes.insert(symbol.into(), ticks);
+        self
+    }
+
+    pub fn with_positions(mut self, positions: &'a [FullPosition]) -> Self {
+        self.positions = Some(positions);
+        self
+    }
+
+    pub fn with_trading_status(mut self, status: &'a TradingStatus) -> Self {
+        self.trading_status = Some(status);
+        self
+    }
+
+    fn ticks_for(&self, leg: &OrderLeg) -> Option<&'a [TickSize]> {
+        if leg.instrument_type != InstrumentType::EquityOption {
+            return self.tick_sizes.get(&leg.symbol).copied();
+        }
+        self.option_tick_sizes
+            .get(&leg.symbol)
+            .copied()
+            .or_else(|| {
+                let option = OptionSymbol::try_from(&leg.symbol).ok()?;
+                self.option_tick_sizes.get(&Symbol(option.root)).copied()
+            })
+    }
+
+    fn position(&self, symbol: &Symbol) -> Option<&'a FullPosition> {
+        self.positions?
+            .iter()
+            .find(|position| &position.symbol == symbol)
+    }
+}
+
+impl Order {
+    /// Checks the order for mistakes that the API would reject it for.
+    pub fn validate(&self, context: &ValidationContext) -> Vec<ValidationError> {
+        let mut errors = vec![];
+        self.validate_legs(&mut errors);
+        self.validate_prices(context, &mut errors);
+        self.validate_positions(context, &mut errors);
+        if let Some(status) = context.trading_status {
+            self.validate_status(status, context, &mut errors);
+        }
+        errors
+    }
+
+    fn validate_legs(&self, errors: &mut Vec<ValidationError>) {
+        if self.legs.is_empty() {
+            errors.push(ValidationError::NoLegs);
+        }
+        if self.legs.len() > MAX_LEGS {
+            errors.push(ValidationError::TooManyLegs {
+                count: self.legs.len(),
+                max: MAX_LEGS,
+            });
+        }
+        let notional = self.order_type == OrderType::NotionalMarket;
+        for leg in &self.legs {
+            match leg.quantity {
+                Some(quantity) if quantity <= Decimal::ZERO => {
+                    errors.push(ValidationError::NonPositiveQuantity {
+                        symbol: leg.symbol.clone(),
+                        quantity,
+                    })
+                }
+                Some(_) if notional => errors.push(ValidationError::UnexpectedQuantity {
+                    symbol: leg.symbol.clone(),
+                }),
+                None if !notional => errors.push(ValidationError::MissingQuantity {
+                    symbol: leg.symbol.clone(),
+                }),
+                _ => {}
+            }
+            let extended = matches!(self.time_in_force, TimeInForce::Ext | TimeInForce::GTCExt);
+            let option = matches!(
+                leg.instrument_type,
+                InstrumentType::EquityOption | InstrumentType::FutureOption
+            );
+            if extended && option {
+                errors.push(ValidationError::TimeInForceNotAllowed {
+                    time_in_force: self.time_in_force.clone(),
+                    instrument_type: leg.instrument_type.clone(),
+                });
+            }
+        }
+    }
+
+    fn validate_prices(&self, context: &ValidationContext, errors: &mut Vec<ValidationError>) {
+        let needs_price = matches!(
+            self.order_type,
+            OrderType::Limit | OrderType::StopLimit | OrderType::MarketableLimit
+        );
+        match self.price {
+            None if needs_price => {
+                errors.push(ValidationError::MissingPrice(self.order_type.clone()))
+            }
+            Some(_) if !needs_price => {
+                errors.push(ValidationError::UnexpectedPrice(self.order_type.clone()))
+            }
+            _ => {}
+        }
+        if matches!(self.order_type, OrderType::Stop | OrderType::StopLimit)
+            && self.stop_trigger.is_none()
+        {
+            errors.push(ValidationError::MissingStopTrigger(self.order_type.clone()));
+        }
+        if self.order_type == OrderType::NotionalMarket
+            && (self.value.is_none() || self.value_effect.is_none())
+        {
+            errors.push(ValidationError::MissingValue);
+        }
+
+        if let Some(price) = self.price {
+            match &self.price_effect {
+                None => errors.push(ValidationError::MissingPriceEffect),
+                Some(actual) => {
+                    if let Some(expected) = self.implied_price_effect() {
+                        if price != Decimal::ZERO && &expected != actual {
+                            errors.push(ValidationError::PriceEffectMismatch {
+                                expected,
+                                actual: actual.clone(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        let ticks = self.legs.iter().find_map(|leg| context.ticks_for(leg));
+        if let Some(ticks) = ticks {
+            for price in self.price.iter().chain(&self.stop_trigger) {
+                if let Some(tick) = TickSize::for_price(ticks, *price) {
+                    if !tick.is_zero() && !(price % tick).is_zero() {
+                        errors.push(ValidationError::PriceNotOnTick {
+                            price: *price,
+                            tick,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Debit when every leg buys, credit when every leg sells.
+    fn implied_price_effect(&self) -> Option<PriceEffect> {
+        let mut sides = self.legs.iter().map(|leg| leg.action.side());
+        let first = sides.next()??;
+        if !sides.all(|side| side == Some(first)) {
+            return None;
+        }
+        Some(match first {
+            OrderSide::Buy => PriceEffect::Debit,
+            OrderSide::Sell => PriceEffect::Credit,
+        })
+    }
+
+    fn validate_positions(&self, context: &ValidationContext, errors: &mut Vec<ValidationError>) {
+        if context.positions.is_none() {
+            return;
+        }
+        for leg in &self.legs {
+            let required = match leg.action {
+                Action::BuyToClose => QuantityDirection::Short,
+                Action::SellToClose => QuantityDirection::Long,
+                _ => continue,
+            };
+            match context
+                .position(&leg.symbol)
+                .filter(|position| position.quantity_direction == required)
+            {
+                None => errors.push(ValidationError::ClosingWithoutPosition {
+                    symbol: leg.symbol.clone(),
+                    action: leg.action.clone(),
+                }),
+                Some(position) if leg.quantity > Some(position.quantity.abs()) => {
+                    errors.push(ValidationError::ClosingExceedsPosition {
+                        symbol: leg.symbol.clone(),
+                        action: leg.action.clone(),
+                        quantity: leg.quantity.unwrap_or_default(),
+                        available: position.quantity.abs(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    fn validate_status(
+        &self,
+        status: &TradingStatus,
+        context: &ValidationContext,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        if status.is_closed {
+            errors.push(ValidationError::AccountClosed);
+        }
+        if status.is_frozen {
+            errors.push(ValidationError::AccountFrozen);
+        }
+        for leg in &self.legs {
+            let (enabled, closing_only) = match leg.instrument_type {
+                InstrumentType::Future | InstrumentType::FutureOption => (
+                    status.is_futures_enabled != Some(false),
+                    status.is_closing_only || status.is_futures_closing_only,
+                ),
+                InstrumentType::Cryptocurrency => (
+                    status.is_cryptocurrency_enabled != Some(false),
+                    status.is_closing_only || status.is_cryptocurrency_closing_only,
+                ),
+                _ => (true, status.is_closing_only),
+            };
+            if !enabled {
+                errors.push(ValidationError::InstrumentNotEnabled(
+                    leg.instrument_type.clone(),
+                ));
+            } else if closing_only && is_opening(leg, context) {
+                errors.push(ValidationError::ClosingOnly(leg.instrument_type.clone()));
+            }
+        }
+    }
+}
+
+/// Whether a leg opens or adds to a position. Plain buys and sells count as
+/// opening unless they offset a known position.
+fn is_opening(leg: &OrderLeg, context: &ValidationContext) -> bool {
+    match leg.action {
+        Action::BuyToOpen | Action::SellToOpen => true,
+        Action::BuyToClose | Action::SellToClose => false,
+        _ => {
+            let offsets = match leg.action.side() {
+                Some(OrderSide::Buy) => QuantityDirection::Short,
+                Some(OrderSide::Sell) => QuantityDirection::Long,
+                None => return true,
+            };
+            context
+                .position(&leg.symbol)
+                .is_none_or(|position| position.quantity_direction != offsets)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::api::order::OrderLegBuilder;
+
+    const CALL: &str = "SPY   240119C00450000";
+
+    fn leg(
+        instrument_type: InstrumentType,
+        symbol: &str,
+        quantity: i64,
+        action: Action,
+    ) -> OrderLeg {
+        OrderLegBuilder::default()
+            .instrument_type(instrument_type)
+            .symbol(symbol)
+            .quantity(Decimal::from(quantity))
+            .action(action)
+            .build()
+            .unwrap()
+    }
+
+    fn limit(price: Decimal, price_effect: PriceEffect, legs: Vec<OrderLeg>) -> Order {
+        Order {
+            time_in_force: TimeInForce::Day,
+            order_type: OrderType::Limit,
+            price: Some(price),
+            price_effect: Some(price_effect),
+            stop_trigger: None,
+            value: None,
+            value_effect: None,
+            external_identifier: None,
+            legs,
+        }
+    }
+
+    fn buy_call() -> Order {
+        limit(
+            Decimal::new(105, 2),
+            PriceEffect::Debit,
+            vec![leg(
+                InstrumentType::EquityOption,
+                CALL,
+                1,
+                Action::BuyToOpen,
+            )],
+        )
+    }
+
+    fn ticks() -> Vec<TickSize> {
+        vec![
+            TickSize {
+                value: Decimal::new(5, 2),
+                threshold: Some(Decimal::from(3)),
+            },
+            TickSize {
+                value: Decimal::new(1, 1),
+                threshold: None,
+            },
+        ]
+    }
+
+    fn status(extra: serde_json::Value) -> TradingStatus {
+        let mut status = json!({
+            "account-number": "5WT00000",
+            "is-closed": false,
+            "is-frozen": false,
+            "is-closing-only": false,
+            "is-in-margin-call": false,
+            "is-pattern-day-trader": false,
+            "options-level": "No Restrictions",
+            "updated-at": "2023-06-01T00:00:00Z",
+        });
+        status
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(status).unwrap()
+    }
+
+    fn validate(order: &Order) -> Vec<ValidationError> {
+        order.validate(&ValidationContext::new())
+    }
+
+    #[test]
+    fn accepts_valid_order() {
+        assert!(validate(&buy_call()).is_empty());
+    }
+
+    #[test]
+    fn checks_legs() {
+        let mut order = buy_call();
+        order.legs.clear();
+        assert_eq!(validate(&order), vec![ValidationError::NoLegs]);
+
+        let mut order = buy_call();
+        order.legs[0].quantity = Some(Decimal::ZERO);
+        assert_eq!(
+            validate(&order),
+            vec![ValidationError::NonPositiveQuantity {
+                symbol: CALL.into(),
+                quantity: Decimal::ZERO,
+            }]
+        );
+
+        let mut order = buy_call();
+        order.time_in_force = TimeInForce::GTCExt;
+        assert_eq!(
+            validate(&order),
+            vec![ValidationError::TimeInForceNotAllowed {
+                time_in_force: TimeInForce::GTCExt,
+                instrument_type: InstrumentType::EquityOption,
+            }]
+        );
+    }
+
+    #[test]
+    fn checks_price_and_effect() {
+        let mut order = buy_call();
+        order.price = None;
+        assert_eq!(
+            validate(&order),
+            vec![ValidationError::MissingPrice(OrderType::Limit)]
+        );
+
+        let mut order = buy_call();
+        order.price_effect = Some(PriceEffect::Credit);
+        assert_eq!(
+            validate(&order),
+            vec![ValidationError::PriceEffectMismatch {
+                expected: PriceEffect::Debit,
+                actual: PriceEffect::Credit,
+            }]
+        );
+
+        let mut order = buy_call();
+        order.order_type = OrderType::StopLimit;
+        assert_eq!(
+            validate(&order),
+            vec![ValidationError::MissingStopTrigger(OrderType::StopLimit)]
+        );
+    }
+
+    #[test]
+    fn checks_notional_orders() {
+        let mut order = buy_call();
+        order.order_type = OrderType::NotionalMarket;
+        order.price = None;
+        order.price_effect = None;
+        assert_eq!(
+            validate(&order),
+            vec![
+                ValidationError::UnexpectedQuantity {
+                    symbol: CALL.into()
+                },
+                ValidationError::MissingValue,
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_option_ticks_by_root() {
+        let ticks = ticks();
+        let context = ValidationContext::new().with_option_tick_sizes("SPY", &ticks);
+        assert!(buy_call().validate(&context).is_empty());
+
+        let mut order = buy_call();
+        order.price = Some(Decimal::new(103, 2));
+        assert_eq!(
+            order.validate(&context),
+            vec![ValidationError::PriceNotOnTick {
+                price: Decimal::new(103, 2),
+                tick: Decimal::new(5, 2),
+            }]
+        );
+
+        order.price = Some(Decimal::new(315, 2));
+        assert_eq!(
+            order.validate(&context),
+            vec![ValidationError::PriceNotOnTick {
+                price: Decimal::new(315, 2),
+                tick: Decimal::new(1, 1),
+            }]
+        );
+    }
+
+    #[test]
+    fn keeps_equity_and_option_ticks_apart() {
+        let ticks = ticks();
+        let mut order = buy_call();
+        order.price = Some(Decimal::new(103, 2));
+        let equity_only = ValidationContext::new().with_tick_sizes("SPY", &ticks);
+        assert!(order.validate(&equity_only).is_empty());
+
+        let stock = limit(
+            Decimal::new(103, 2),
+            PriceEffect::Debit,
+            vec![leg(InstrumentType::Equity, "SPY", 1, Action::Buy)],
+        );
+        let options_only = ValidationContext::new().with_option_tick_sizes("SPY", &ticks);
+        assert!(stock.validate(&options_only).is_empty());
+        assert_eq!(stock.validate(&equity_only).len(), 1);
+    }
+
+    #[test]
+    fn checks_closing_legs_against_positions() {
+        let positions = [FullPosition::test(InstrumentType::EquityOption, CALL, 1)];
+        let context = ValidationContext::new().with_positions(&positions);
+        let close = |quantity| {
+            limit(
+                Decimal::ONE,
+                PriceEffect::Credit,
+                vec![leg(
+                    InstrumentType::EquityOption,
+                    CALL,
+                    quantity,
+                    Action::SellToClose,
+                )],
+            )
+        };
+        assert!(close(1).validate(&context).is_empty());
+        assert_eq!(
+            close(2).validate(&context),
+            vec![ValidationError::ClosingExceedsPosition {
+                symbol: CALL.into(),
+                action: Action::SellToClose,
+                quantity: Decimal::from(2),
+                available: Decimal::ONE,
+            }]
+        );
+        assert_eq!(
+            close(1).validate(&ValidationContext::new().with_positions(&[])),
+            vec![ValidationError::ClosingWithoutPosition {
+                symbol: CALL.into(),
+                action: Action::SellToClose,
+            }]
+        );
+    }
+
+    #[test]
+    fn checks_trading_status() {
+        let future = limit(
+            Decimal::from(4000),
+            PriceEffect::Debit,
+            vec![leg(InstrumentType::Future, "/ESZ3", 1, Action::Buy)],
+        );
+        let missing = status(json!({}));
+        let context = ValidationContext::new().with_trading_status(&missing);
+        assert!(future.validate(&context).is_empty());
+
+        let disabled = status(json!({ "is-futures-enabled": false }));
+        let context = ValidationContext::new().with_trading_status(&disabled);
+        assert_eq!(
+            future.validate(&context),
+            vec![ValidationError::InstrumentNotEnabled(
+                InstrumentType::Future
+            )]
+        );
+
+        let closing_only = status(json!({ "is-futures-closing-only": true }));
+        let context = ValidationContext::new()
+            .with_trading_status(&closing_only)
+            .with_positions(&[]);
+        assert_eq!(
+            future.validate(&context),
+            vec![ValidationError::ClosingOnly(InstrumentType::Future)]
+        );
+        assert!(buy_call().validate(&context).is_empty());
+
+        let frozen = status(json!({ "is-frozen": true }));
+        let context = ValidationContext::new().with_trading_status(&frozen);
+        assert_eq!(
+            buy_call().validate(&context),
+            vec![ValidationError::AccountFrozen]
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use std::future::Future;
+
+use crate::accounts::{Account, AccountNumber, Balance};
+use crate::api::instrument::EquityInstrumentInfo;
+use crate::api::option_chain::{NestedOptionChain, OptionInfo};
+use crate::api::order::{
+    DryRunResult, InstrumentType, LiveOrderRecord, Order, OrderId, OrderPlacedResult, Symbol,
+};
+use crate::api::position::FullPosition;
+use crate::api::quote_streaming::DxFeedSymbol;
+use crate::paper_trading::PaperAccount;
+use crate::{Result, TastyTrade};
+
+/// Operations on a single brokerage account.
+///
+/// Implemented by [`Account`] and [`PaperAccount`], so strategy code written
+/// against this trait can run live, simulated, or against a test double.
+pub trait AccountApi {
+    fn number(&self) -> AccountNumber;
+
+    fn balance(&self) -> impl Future<Output = Result<Balance>> + Send;
+
+    fn positions(&self) -> impl Future<Output = Result<Vec<FullPosition>>> + Send;
+
+    fn live_orders(&self) -> impl Future<Output = Result<Vec<LiveOrderRecord>>> + Send;
+
+    fn dry_run(&self, order: &Order) -> impl Future<Output = Result<DryRunResult>> + Send;
+
+    fn place_order(&self, order: &Order) -> impl Future<Output = Result<OrderPlacedResult>> + Send;
+
+    fn cancel_order(&self, id: OrderId) -> impl Future<Output = Result<LiveOrderRecord>> + Send;
+}
+
+/// Account discovery and instrument lookups, implemented by [`TastyTrade`].
+pub trait Broker {
+    type Account<'a>: AccountApi
+    where
+        Self: 'a;
+
+    fn accounts(&self) -> impl Future<Output = Result<Vec<Self::Account<'_>>>> + Send;
+
+    fn account(
+        &self,
+        account_number: AccountNumber,
+    ) -> impl Future<Output = Result<Option<Self::Account<'_>>>> + Send;
+
+    fn get_equity_info(
+        &self,
+        symbol: &Symbol,
+    ) -> impl Future<Output = Result<EquityInstrumentInfo>> + Send;
+
+    fn get_option_info(&self, symbol: &Symbol) -> impl Future<Output = Result<OptionInfo>> + Send;
+
+    fn nested_option_chain_for(
+        &self,
+        symbol: &Symbol,
+    ) -> impl Future<Output = Result<NestedOptionChain>> + Send;
+
+    fn get_streamer_symbol(
+        &self,
+        instrument_type: &InstrumentType,
+        symbol: &Symbol,
+    ) -> impl Future<Output = Result<DxFeedSymbol>> + Send;
+}
+
+impl AccountApi for Account<'_> {
+    fn number(&self) -> AccountNumber {
+        Account::number(self)
+    }
+
+    fn balance(&self) -> impl Future<Output = Result<Balance>> + Send {
+        Account::balance(self)
+    }
+
+    fn positions(&self) -> impl Future<Output = Result<Vec<FullPosition>>> + Send {
+        Account::positions(self)
+    }
+
+    fn live_orders(&self) -> impl Future<Output = Result<Vec<LiveOrderRecord>>> + Send {
+        Account::live_orders(self)
+    }
+
+    fn dry_run(&self, order: &Order) -> impl Future<Output = Result<DryRunResult>> + Send {
+        Account::dry_run(self, order)
+    }
+
+    fn place_order(&self, order: &Order) -> impl Future<Output = Result<OrderPlacedResult>> + Send {
+        Account::place_order(self, order)
+    }
+
+    fn cancel_order(&self, id: OrderId) -> impl Future<Output = Result<LiveOrderRecord>> + Send {
+        Account::cancel_order(self, id)
+    }
+}
+
+impl AccountApi for PaperAccount {
+    fn number(&self) -> AccountNumber {
+        PaperAccount::number(self)
+    }
+
+    fn balance(&self) -> impl Future<Output = Result<Balance>> + Send {
+        PaperAccount::balance(self)
+    }
+
+    fn positions(&self) -> impl Future<Output = Result<Vec<FullPosition>>> + Send {
+        PaperAccount::positions(self)
+    }
+
+    fn live_orders(&self) -> impl Future<Output = Result<Vec<LiveOrderRecord>>> + Send {
+        PaperAccount::live_orders(self)
+    }
+
+    fn dry_run(&self, order: &Order) -> impl Future<Output = Result<DryRunResult>> + Send {
+        PaperAccount::dry_run(self, order)
+    }
+
+    fn place_order(&self, order: &Order) -> impl Future<Output = Result<OrderPlacedResult>> + Send {
+        PaperAccount::place_order(self, order)
+    }
+
+    fn cancel_order(&self, id: OrderId) -> impl Future<Output = Result<LiveOrderRecord>> + Send {
+        PaperAccount::cancel_order(self, id)
+    }
+}
+
+impl Broker for TastyTrade {
+    type Account<'a> = Account<'a>;
+
+    fn accounts(&self) -> impl Future<Output = Result<Vec<Account<'_>>>> + Send {
+        TastyTrade::accounts(self)
+    }
+
+    fn account(
+        &self,
+        account_number: AccountNumber,
+    ) -> impl Future<Output = Result<Option<Account<'_>>>> + Send {
+        TastyTrade::account(self, account_number)
+    }
+
+    fn get_equity_info(
+        &self,
+        symbol: &Symbol,
+    ) -> impl Future<Output = Result<EquityInstrumentInfo>> + Send {
+        TastyTrade::get_equity_info(self, symbol)
+    }
+
+    fn get_option_info(&self, symbol: &Symbol) -> impl Future<Output = Result<OptionInfo>> + Send {
+        TastyTrade::get_option_info(self, symbol)
+    }
+
+    fn nested_option_chain_for(
+        &self,
+        symbol: &Symbol,
+    ) -> impl Future<Output = Result<NestedOptionChain>> + Send {
+        TastyTrade::nested_option_chain_for(self, symbol.clone())
+    }
+
+    fn get_streamer_symbol(
+        &self,
+        instrument_type: &InstrumentType,
+        symbol: &Symbol,
+    ) -> impl Future<Output = Result<DxFeedSymbol>> + Send {
+        TastyTrade::get_streamer_symbol(self, instrument_type, symbol)
+    }
+}
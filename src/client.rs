@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use reqwest::header;
 use reqwest::header::HeaderMap;
 use reqwest::header::HeaderValue;
@@ -14,21 +16,39 @@ use crate::api::base::Response;
 use crate::api::base::Result;
 use crate::api::base::TastyApiResponse;
 
-//use crate::api::base::TastyError;
+use crate::api::base::TastyError;
 use crate::api::login::LoginCredentials;
 use crate::api::login::LoginResponse;
+use crate::instrument_cache::InstrumentCache;
 
 //use reqwest_inspect_json::InspectJson;
 
 pub const BASE_URL: &str = "https://api.tastyworks.com";
 pub const BASE_DEMO_URL: &str = "https://api.cert.tastyworks.com";
 
+/// What a [`TastyTrade`] client is allowed to do, fixed when it logs in.
+///
+/// Outside `Full`, every POST, PUT or DELETE other than an order dry run fails
+/// with [`crate::TastyError::Forbidden`], whichever API sends it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClientMode {
+    #[default]
+    Full,
+    /// Order placement, replacement and cancellation fail with [`crate::TastyError::Forbidden`].
+    ReadOnly,
+    /// Placement and replacement are dry-run and return simulated results;
+    /// cancellation fails with [`crate::TastyError::Forbidden`].
+    DryRunOnly,
+}
+
 #[derive(Debug, Clone)]
 pub struct TastyTrade {
     pub(crate) client: reqwest::Client,
     pub(crate) session_token: String,
     base_url: &'static str,
     pub(crate) demo: bool,
+    mode: ClientMode,
+    pub(crate) instrument_cache: Option<Arc<InstrumentCache>>,
 }
 
 pub trait FromTastyResponse<T: DeserializeOwned> {
@@ -50,31 +70,74 @@ impl<T: DeserializeOwned> FromTastyResponse<Items<T>> for Paginated<T> {
     }
 }
 
+/// Percent-encodes `segment` for use as a single URL path segment, so symbols
+/// such as `/ESZ3` or `BTC/USD` don't split the path.
+pub(crate) fn path_segment(segment: &str) -> String {
+    let mut url = url::Url::parse("http://localhost/").unwrap();
+    url.path_segments_mut()
+        .unwrap()
+        .pop_if_empty()
+        .push(segment);
+    url.path()[1..].to_owned()
+}
+
 impl TastyTrade {
     pub async fn login(login: &str, password: &str, remember_me: bool) -> Result<Self> {
+        Self::login_with_mode(login, password, remember_me, ClientMode::Full).await
+    }
+
+    pub async fn login_demo(login: &str, password: &str, remember_me: bool) -> Result<Self> {
+        Self::login_demo_with_mode(login, password, remember_me, ClientMode::Full).await
+    }
+
+    pub async fn login_with_mode(
+        login: &str,
+        password: &str,
+        remember_me: bool,
+        mode: ClientMode,
+    ) -> Result<Self> {
         let creds = Self::do_login_request(login, password, remember_me, BASE_URL).await?;
         let client = Self::create_client(&creds);
 
         Ok(Self {
             client,
             session_token: creds.session_token,
-            base_url: "https://api.tastyworks.com",
+            base_url: BASE_URL,
             demo: false,
+            mode,
+            instrument_cache: None,
         })
     }
 
-    pub async fn login_demo(login: &str, password: &str, remember_me: bool) -> Result<Self> {
+    pub async fn login_demo_with_mode(
+        login: &str,
+        password: &str,
+        remember_me: bool,
+        mode: ClientMode,
+    ) -> Result<Self> {
         let creds = Self::do_login_request(login, password, remember_me, BASE_DEMO_URL).await?;
         let client = Self::create_client(&creds);
 
         Ok(Self {
             client,
             session_token: creds.session_token,
-            base_url: "https://api.cert.tastyworks.com",
+            base_url: BASE_DEMO_URL,
             demo: true,
+            mode,
+            instrument_cache: None,
         })
     }
 
+    pub fn mode(&self) -> ClientMode {
+        self.mode
+    }
+
+    /// Whether the mode permits a non-GET request to `url`. Outside
+    /// [`ClientMode::Full`] only order dry runs are sent.
+    fn allows_write(&self, url: &str) -> bool {
+        self.mode == ClientMode::Full || url.ends_with("/dry-run")
+    }
+
     fn create_client(creds: &LoginResponse) -> reqwest::Client {
         let mut headers = HeaderMap::new();
 
@@ -129,11 +192,14 @@ impl TastyTrade {
         Ok(response)
     }
 
-    pub async fn get_with_query<T, R, U>(&self, url: U, query: &[(&str, &str)]) -> Result<R>
+    /// Sends a GET request with `query` encoded into the URL. Any serializable
+    /// list of pairs works, so keys may repeat and values may be owned.
+    pub async fn get_with_query<T, R, U, Q>(&self, url: U, query: &Q) -> Result<R>
     where
         T: DeserializeOwned,
         R: FromTastyResponse<T>,
         U: AsRef<str>,
+        Q: Serialize + ?Sized,
     {
         let url = format!("{}{}", self.base_url, url.as_ref());
 
@@ -157,7 +223,7 @@ impl TastyTrade {
     }
 
     pub async fn get<T: DeserializeOwned, U: AsRef<str>>(&self, url: U) -> Result<T> {
-        self.get_with_query(url, &[]).await
+        self.get_with_query(url, &[] as &[(&str, &str)]).await
     }
 
     pub async fn post<R, P, U>(&self, url: U, payload: P) -> Result<R>
@@ -166,6 +232,9 @@ impl TastyTrade {
         P: Serialize,
         U: AsRef<str>,
     {
+        if !self.allows_write(url.as_ref()) {
+            return Err(TastyError::Forbidden(self.mode));
+        }
         let url = format!("{}{}", self.base_url, url.as_ref());
         let result = self
             .client
@@ -185,11 +254,39 @@ impl TastyTrade {
         }
     }
 
+    pub async fn put<R, P, U>(&self, url: U, payload: P) -> Result<R>
+    where
+        R: DeserializeOwned,
+        P: Serialize,
+        U: AsRef<str>,
+    {
+        if !self.allows_write(url.as_ref()) {
+            return Err(TastyError::Forbidden(self.mode));
+        }
+        let url = format!("{}{}", self.base_url, url.as_ref());
+        let result = self
+            .client
+            .put(url)
+            .body(serde_json::to_string(&payload).unwrap())
+            .send()
+            .await?
+            .json::<TastyApiResponse<R>>()
+            .await?;
+
+        match result {
+            TastyApiResponse::Success(s) => Ok(s.data),
+            TastyApiResponse::Error { error } => Err(error.into()),
+        }
+    }
+
     pub async fn delete<R, U>(&self, url: U) -> Result<R>
     where
         R: DeserializeOwned,
         U: AsRef<str>,
     {
+        if !self.allows_write(url.as_ref()) {
+            return Err(TastyError::Forbidden(self.mode));
+        }
         let url = format!("{}{}", self.base_url, url.as_ref());
         let result = self
             .client
@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::api::instrument::EquityInstrumentInfo;
+use crate::api::option_chain::OptionInfo;
+use crate::api::order::{InstrumentType, Symbol};
+use crate::api::quote_streaming::DxFeedSymbol;
+use crate::{Result, TastyTrade};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct Entry<T> {
+    value: T,
+    fetched_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct Entries {
+    #[serde(default)]
+    equities: HashMap<Symbol, Entry<EquityInstrumentInfo>>,
+    #[serde(default)]
+    options: HashMap<Symbol, Entry<OptionInfo>>,
+    #[serde(default)]
+    streamer_symbols: HashMap<InstrumentType, HashMap<Symbol, Entry<DxFeedSymbol>>>,
+}
+
+/// Instrument metadata kept between lookups.
+///
+/// Attach it with [`TastyTrade::with_instrument_cache`] and
+/// [`TastyTrade::get_equity_info`], [`TastyTrade::get_option_info`] and
+/// [`TastyTrade::get_streamer_symbol`] answer from it while entries are
+/// younger than the TTL. With a store, the entries are read from a JSON file
+/// on creation and written back by [`InstrumentCache::save`].
+#[derive(Debug)]
+pub struct InstrumentCache {
+    ttl: Duration,
+    store: Option<PathBuf>,
+    entries: Mutex<Entries>,
+}
+
+impl InstrumentCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            store: None,
+            entries: Mutex::new(Entries::default()),
+        }
+    }
+
+    /// A cache persisted to the JSON file at `path`, loading it if it exists.
+    pub fn with_store(ttl: Duration, path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let entries = match std::fs::read(&path) {
+            Ok(contents) => serde_json::from_slice(&contents)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Entries::default(),
+            Err(e) => return Err(e),
+        };
+        Ok(Self {
+            ttl,
+            store: Some(path),
+            entries: Mutex::new(entries),
+        })
+    }
+
+    pub fn store(&self) -> Option<&Path> {
+        self.store.as_deref()
+    }
+
+    /// Writes the entries to the store. Does nothing without one.
+    pub fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.store else {
+            return Ok(());
+        };
+        let contents = serde_json::to_vec(&*self.lock())?;
+        let temp = path.with_extension("tmp");
+        std::fs::write(&temp, contents)?;
+        std::fs::rename(temp, path)
+    }
+
+    /// Drops everything cached for `symbol`.
+    pub fn invalidate(&self, symbol: &Symbol) {
+        let mut entries = self.lock();
+        entries.equities.remove(symbol);
+        entries.options.remove(symbol);
+        for symbols in entries.streamer_symbols.values_mut() {
+            symbols.remove(symbol);
+        }
+    }
+
+    pub fn clear(&self) {
+        *self.lock() = Entries::default();
+    }
+
+    /// Drops entries older than the TTL.
+    pub fn purge_expired(&self) {
+        let mut entries = self.lock();
+        entries.equities.retain(|_, entry| self.is_fresh(entry));
+        entries.options.retain(|_, entry| self.is_fresh(entry));
+        for symbols in entries.streamer_symbols.values_mut() {
+            symbols.retain(|_, entry| self.is_fresh(entry));
+        }
+    }
+
+    pub fn equity_info(&self, symbol: &Symbol) -> Option<EquityInstrumentInfo> {
+        self.fresh(self.lock().equities.get(symbol)?)
+    }
+
+    pub fn option_info(&self, symbol: &Symbol) -> Option<OptionInfo> {
+        self.fresh(self.lock().options.get(symbol)?)
+    }
+
+    pub fn streamer_symbol(
+        &self,
+        instrument_type: &InstrumentType,
+        symbol: &Symbol,
+    ) -> Option<DxFeedSymbol> {
+        let entries = self.lock();
+        self.fresh(entries.streamer_symbols.get(instrument_type)?.get(symbol)?)
+    }
+
+    pub fn insert_equity_info(&self, info: EquityInstrumentInfo) {
+        let mut entries = self.lock();
+        insert(
+            &mut entries,
+            InstrumentType::Equity,
+            info.symbol.clone(),
+            info.streamer_symbol.clone(),
+        );
+        entries.equities.insert(info.symbol.clone(), entry(info));
+    }
+
+    pub fn insert_option_info(&self, info: OptionInfo) {
+        let mut entries = self.lock();
+        insert(
+            &mut entries,
+            InstrumentType::EquityOption,
+            info.symbol.clone(),
+            info.streamer_symbol.clone(),
+        );
+        entries.options.insert(info.symbol.clone(), entry(info));
+    }
+
+    pub fn insert_streamer_symbol(
+        &self,
+        instrument_type: InstrumentType,
+        symbol: Symbol,
+        streamer_symbol: DxFeedSymbol,
+    ) {
+        insert(&mut self.lock(), instrument_type, symbol, streamer_symbol);
+    }
+
+    fn is_fresh<T>(&self, entry: &Entry<T>) -> bool {
+        // A negative age means the clock went backwards; trust the entry.
+        (Utc::now() - entry.fetched_at)
+            .to_std()
+            .map_or(true, |age| age < self.ttl)
+    }
+
+    fn fresh<T: Clone>(&self, entry: &Entry<T>) -> Option<T> {
+        self.is_fresh(entry).then(|| entry.value.clone())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Entries> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn entry<T>(value: T) -> Entry<T> {
+    Entry {
+        value,
+        fetched_at: Utc::now(),
+    }
+}
+
+fn insert(
+    entries: &mut Entries,
+    instrument_type: InstrumentType,
+    symbol: Symbol,
+    streamer_symbol: DxFeedSymbol,
+) {
+    entries
+        .streamer_symbols
+        .entry(instrument_type)
+        .or_default()
+        .insert(symbol, entry(streamer_symbol));
+}
+
+impl TastyTrade {
+    /// Serves instrument lookups from `cache`. Clones of this client share it.
+    pub fn with_instrument_cache(mut self, cache: Arc<InstrumentCache>) -> Self {
+        self.instrument_cache = Some(cache);
+        self
+    }
+
+    pub fn instrument_cache(&self) -> Option<&Arc<InstrumentCache>> {
+        self.instrument_cache.as_ref()
+    }
+
+    /// Fills the cache with `symbols`, batching the lookups where the API allows.
+    /// Does nothing without a cache.
+    pub async fn warm_instrument_cache(&self, symbols: &[(InstrumentType, Symbol)]) -> Result<()> {
+        let Some(cache) = &self.instrument_cache else {
+            return Ok(());
+        };
+        let of_type = |wanted: InstrumentType| -> Vec<&Symbol> {
+            symbols
+                .iter()
+                .filter(|(instrument_type, _)| instrument_type == &wanted)
+                .map(|(_, symbol)| symbol)
+                .collect()
+        };
+
+        for info in self
+            .get_equity_infos(&of_type(InstrumentType::Equity))
+            .await?
+        {
+            cache.insert_equity_info(info);
+        }
+        for info in self
+            .get_option_infos(&of_type(InstrumentType::EquityOption))
+            .await?
+        {
+            cache.insert_option_info(info);
+        }
+        for future in self.get_futures(&of_type(InstrumentType::Future)).await? {
+            cache.insert_streamer_symbol(
+                InstrumentType::Future,
+                future.symbol,
+                future.streamer_symbol,
+            );
+        }
+        for (instrument_type, symbol) in symbols {
+            let batched = matches!(
+                instrument_type,
+                InstrumentType::Equity | InstrumentType::EquityOption | InstrumentType::Future
+            );
+            if !batched {
+                self.get_streamer_symbol(instrument_type, symbol).await?;
+            }
+        }
+        Ok(())
+    }
+}
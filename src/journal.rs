@@ -0,0 +1,166 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::accounts::AccountNumber;
+use crate::api::base::{ApiError, TastyError};
+use crate::api::order::{DryRunResult, LiveOrderRecord, OrderId, OrderPlacedResult};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum JournalOperation {
+    DryRun,
+    PlaceOrder,
+    ReplaceOrder,
+    CancelOrder,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", content = "body", rename_all = "kebab-case")]
+pub enum JournalResponse {
+    /// Written before the request is sent. The entry with the response
+    /// follows, unless the process stopped before it arrived.
+    Pending,
+    DryRun(DryRunResult),
+    OrderPlaced(OrderPlacedResult),
+    OrderReplaced(LiveOrderRecord),
+    OrderCancelled(LiveOrderRecord),
+    ApiError(ApiError),
+    /// Any other failure, such as a network error, described by its message.
+    Error(String),
+}
+
+impl From<&TastyError> for JournalResponse {
+    fn from(error: &TastyError) -> Self {
+        match error {
+            TastyError::Api(error) => JournalResponse::ApiError(error.clone()),
+            other => JournalResponse::Error(other.to_string()),
+        }
+    }
+}
+
+/// One line of the journal.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct JournalEntry {
+    pub requested_at: DateTime<Utc>,
+    /// `None` for [`JournalResponse::Pending`] entries.
+    #[serde(default)]
+    pub responded_at: Option<DateTime<Utc>>,
+    pub account_number: AccountNumber,
+    pub tag: Option<String>,
+    pub operation: JournalOperation,
+    /// The order body sent to the API, for dry runs and submissions.
+    pub request: Option<serde_json::Value>,
+    pub order_id: Option<OrderId>,
+    pub response: JournalResponse,
+}
+
+/// An append-only JSONL log of order requests and their responses.
+///
+/// Entries are appended to `path`. Once the file would grow beyond the rotation
+/// size it is renamed to `path.1`, `path.2` and so on; rotated files are never
+/// deleted. Attach a journal to an account with
+/// [`crate::accounts::Account::with_journal`].
+#[derive(Debug)]
+pub struct OrderJournal {
+    path: PathBuf,
+    max_bytes: u64,
+    file: Mutex<Option<File>>,
+}
+
+impl OrderJournal {
+    pub const DEFAULT_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let journal = Self {
+            path: path.into(),
+            max_bytes: Self::DEFAULT_MAX_BYTES,
+            file: Mutex::new(None),
+        };
+        *journal.file.lock().unwrap() = Some(journal.open_current()?);
+        Ok(journal)
+    }
+
+    /// Sets the size in bytes after which the journal file is rotated.
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, entry: &JournalEntry) -> io::Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        let current = match file.take() {
+            Some(current) => current,
+            None => self.open_current()?,
+        };
+        let size = current.metadata()?.len();
+        let mut current = if size > 0 && size + line.len() as u64 > self.max_bytes {
+            drop(current);
+            fs::rename(
+                &self.path,
+                rotated_path(&self.path, self.rotated_count() + 1),
+            )?;
+            self.open_current()?
+        } else {
+            current
+        };
+        current.write_all(&line)?;
+        current.flush()?;
+        *file = Some(current);
+        Ok(())
+    }
+
+    /// Reads every entry of the journal at `path`, including rotated files, oldest first.
+    pub fn replay(path: impl AsRef<Path>) -> io::Result<Vec<JournalEntry>> {
+        let path = path.as_ref();
+        let mut files: Vec<PathBuf> = (1..)
+            .map(|n| rotated_path(path, n))
+            .take_while(|rotated| rotated.exists())
+            .collect();
+        if path.exists() {
+            files.push(path.to_owned());
+        }
+
+        let mut entries = vec![];
+        for file in files {
+            for line in BufReader::new(File::open(file)?).lines() {
+                let line = line?;
+                if !line.trim().is_empty() {
+                    entries.push(serde_json::from_str(&line)?);
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    fn open_current(&self) -> io::Result<File> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+    }
+
+    fn rotated_count(&self) -> usize {
+        (1..)
+            .take_while(|n| rotated_path(&self.path, *n).exists())
+            .count()
+    }
+}
+
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{n}"));
+    PathBuf::from(name)
+}
@@ -1,8 +1,13 @@
 #![feature(async_iterator)]
 
 pub mod api;
+pub mod broker;
 pub mod client;
+pub mod instrument_cache;
+pub mod journal;
+pub mod paper_trading;
 pub mod quote_streamer;
+pub mod watchdog;
 
 pub use api::accounts;
 pub use api::base::Result;
@@ -0,0 +1,978 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use dxfeed::EventData;
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+
+use crate::accounts::{AccountNumber, Balance};
+use crate::api::account_streaming::AccountMessage;
+use crate::api::base::{ApiError, Result};
+use crate::api::money::SignedAmount;
+use crate::api::order::{
+    AsSymbol, BuyingPowerEffect, DryRunRecord, DryRunResult, FeeCalculation, Fill, InstrumentType,
+    LiveOrderLeg, LiveOrderRecord, Order, OrderId, OrderPlacedResult, OrderSide, OrderStatus,
+    OrderType, PriceEffect, Symbol, Warning,
+};
+use crate::api::position::{BriefPosition, FullPosition, QuantityDirection};
+use crate::api::symbology::OptionSymbol;
+use crate::quote_streamer::QuoteSubscription;
+
+/// Fees charged by a [`PaperAccount`] on every fill.
+#[derive(Debug, Clone, Default)]
+pub struct FeeSchedule {
+    pub per_order: Decimal,
+    /// Charged per share or coin of equity and cryptocurrency legs.
+    pub per_share: Decimal,
+    /// Charged per contract of option and futures legs.
+    pub per_contract: Decimal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaperQuote {
+    pub bid: Decimal,
+    pub ask: Decimal,
+}
+
+impl PaperQuote {
+    pub fn mid(&self) -> Decimal {
+        (self.bid + self.ask) / Decimal::TWO
+    }
+
+    fn price_for(&self, side: OrderSide) -> Decimal {
+        match side {
+            OrderSide::Buy => self.ask,
+            OrderSide::Sell => self.bid,
+        }
+    }
+}
+
+/// A simulated account exposing the same operations as [`crate::accounts::Account`].
+///
+/// Orders never leave the process. They are filled against quotes supplied through
+/// [`PaperAccount::update_quote`], [`PaperAccount::process_event`] or
+/// [`PaperAccount::run`]: buys fill at the ask and sells at the bid, limit orders
+/// once the net price is at or better than the limit, and stop orders once the
+/// first leg's quote crosses the stop trigger. Every change is also published as
+/// an [`AccountMessage`] on [`PaperAccount::events`].
+#[derive(Debug)]
+pub struct PaperAccount {
+    account_number: AccountNumber,
+    fees: FeeSchedule,
+    state: Mutex<PaperState>,
+    event_sender: flume::Sender<AccountMessage>,
+    event_receiver: flume::Receiver<AccountMessage>,
+}
+
+#[derive(Debug, Default)]
+struct PaperState {
+    cash: Decimal,
+    positions: HashMap<Symbol, PaperPosition>,
+    working: Vec<WorkingOrder>,
+    quotes: HashMap<Symbol, PaperQuote>,
+    streamer_symbols: HashMap<String, Symbol>,
+    multipliers: HashMap<Symbol, Decimal>,
+    next_order_id: u64,
+    next_fill_id: u64,
+}
+
+#[derive(Debug)]
+struct PaperPosition {
+    instrument_type: InstrumentType,
+    quantity: Decimal,
+    average_open_price: Decimal,
+    realized: Decimal,
+    created_at: String,
+    updated_at: String,
+}
+
+#[derive(Debug)]
+struct WorkingOrder {
+    order: Order,
+    record: LiveOrderRecord,
+    triggered: bool,
+}
+
+impl PaperAccount {
+    pub fn new(account_number: impl Into<AccountNumber>, starting_cash: Decimal) -> Self {
+        let (event_sender, event_receiver) = flume::unbounded();
+        Self {
+            account_number: account_number.into(),
+            fees: FeeSchedule::default(),
+            state: Mutex::new(PaperState {
+                cash: starting_cash,
+                next_order_id: 1,
+                next_fill_id: 1,
+                ..Default::default()
+            }),
+            event_sender,
+            event_receiver,
+        }
+    }
+
+    pub fn with_fees(mut self, fees: FeeSchedule) -> Self {
+        self.fees = fees;
+        self
+    }
+
+    pub fn number(&self) -> AccountNumber {
+        self.account_number.clone()
+    }
+
+    /// Receiver of order, balance and position updates, shaped like the account streamer's.
+    pub fn events(&self) -> flume::Receiver<AccountMessage> {
+        self.event_receiver.clone()
+    }
+
+    /// Overrides the contract multiplier of a symbol. Equity options default to 100,
+    /// everything else to 1.
+    pub fn set_multiplier(&self, symbol: impl AsSymbol, multiplier: Decimal) {
+        self.lock()
+            .multipliers
+            .insert(symbol.as_symbol(), multiplier);
+    }
+
+    /// Maps a DxFeed symbol to the tastytrade symbol orders are placed with.
+    /// Unmapped DxFeed symbols are used as-is.
+    pub fn map_streamer_symbol(&self, streamer_symbol: impl AsSymbol, symbol: impl AsSymbol) {
+        self.lock()
+            .streamer_symbols
+            .insert(streamer_symbol.as_symbol().0, symbol.as_symbol());
+    }
+
+    /// Records a new quote and fills any working orders it makes marketable.
+    pub fn update_quote(&self, symbol: impl AsSymbol, bid: Decimal, ask: Decimal) {
+        let mut state = self.lock();
+        state
+            .quotes
+            .insert(symbol.as_symbol(), PaperQuote { bid, ask });
+        self.match_working(&mut state);
+    }
+
+    /// Feeds a DxFeed quote event into the simulation. Other events are ignored.
+    pub fn process_event(&self, event: &dxfeed::Event) {
+        let EventData::Quote(quote) = &event.data else {
+            return;
+        };
+        let (Some(bid), Some(ask)) = (
+            Decimal::from_f64(quote.bid_price),
+            Decimal::from_f64(quote.ask_price),
+        ) else {
+            return;
+        };
+        let symbol = self
+            .lock()
+            .streamer_symbols
+            .get(&event.sym)
+            .cloned()
+            .unwrap_or_else(|| event.sym.as_symbol());
+        self.update_quote(symbol, bid, ask);
+    }
+
+    /// Processes quote events from `subscription` until it is closed.
+    pub async fn run(&self, subscription: &QuoteSubscription) {
+        while let Ok(event) = subscription.get_event().await {
+            self.process_event(&event);
+        }
+    }
+
+    pub fn quote(&self, symbol: impl AsSymbol) -> Option<PaperQuote> {
+        self.lock().quotes.get(&symbol.as_symbol()).copied()
+    }
+
+    pub async fn balance(&self) -> Result<Balance> {
+        let state = self.lock();
+        Ok(self.balance_of(&state))
+    }
+
+    pub async fn positions(&self) -> Result<Vec<FullPosition>> {
+        let state = self.lock();
+        Ok(state
+            .positions
+            .iter()
+            .map(|(symbol, position)| self.full_position(&state, symbol, position))
+            .collect())
+    }
+
+    pub async fn live_orders(&self) -> Result<Vec<LiveOrderRecord>> {
+        Ok(self
+            .lock()
+            .working
+            .iter()
+            .map(|working| working.record.clone())
+            .collect())
+    }
+
+    pub async fn dry_run(&self, order: &Order) -> Result<DryRunResult> {
+        if order.legs.is_empty() {
+            return Err(api_error("invalid_order", "Order has no legs"));
+        }
+        if order.legs.iter().any(|leg| leg.quantity.is_none()) {
+            return Err(api_error(
+                "unsupported_order",
+                "Paper accounts do not support notional market orders",
+            ));
+        }
+        let state = self.lock();
+        let record = self.record(order, OrderId(0), OrderStatus::Received);
+        let (buying_power_effect, fee_calculation, warnings) = self.preview(&state, order);
+        Ok(DryRunResult {
+            order: DryRunRecord {
+                account_number: record.account_number,
+                time_in_force: record.time_in_force,
+                order_type: record.order_type,
+                size: record.size,
+                underlying_symbol: record.underlying_symbol,
+                price: record.price,
+                status: record.status,
+                cancellable: record.cancellable,
+                editable: record.editable,
+                edited: record.edited,
+                legs: order.legs.clone(),
+            },
+            warnings,
+            buying_power_effect,
+            fee_calculation,
+        })
+    }
+
+    pub async fn place_order(&self, order: &Order) -> Result<OrderPlacedResult> {
+        if order.legs.is_empty() {
+            return Err(api_error("invalid_order", "Order has no legs"));
+        }
+        if order.legs.iter().any(|leg| leg.quantity.is_none()) {
+            return Err(api_error(
+                "unsupported_order",
+                "Paper accounts do not support notional market orders",
+            ));
+        }
+        let mut state = self.lock();
+        let (buying_power_effect, fee_calculation, warnings) = self.preview(&state, order);
+        let cost = -(buying_power_effect.impact + fee_calculation.total_fees).value();
+        if cost > state.cash {
+            return Err(api_error(
+                "insufficient_funds",
+                "Order exceeds the available cash of the paper account",
+            ));
+        }
+
+        let id = OrderId(state.next_order_id);
+        state.next_order_id += 1;
+        let record = self.record(order, id, OrderStatus::Live);
+        self.emit(AccountMessage::Order(Box::new(record.clone())));
+        state.working.push(WorkingOrder {
+            order: order.clone(),
+            record,
+            triggered: false,
+        });
+        self.match_working(&mut state);
+
+        let record = state
+            .working
+            .iter()
+            .find(|working| working.record.id == id)
+            .map(|working| working.record.clone())
+            .unwrap_or_else(|| {
+                let mut record = self.record(order, id, OrderStatus::Filled);
+                record.cancellable = false;
+                record.editable = false;
+                record
+            });
+        Ok(OrderPlacedResult {
+            order: record,
+            warnings,
+            buying_power_effect,
+            fee_calculation,
+        })
+    }
+
+    pub async fn cancel_order(&self, id: OrderId) -> Result<LiveOrderRecord> {
+        let mut state = self.lock();
+        let Some(index) = state
+            .working
+            .iter()
+            .position(|working| working.record.id == id)
+        else {
+            return Err(api_error(
+                "order_not_found",
+                "Order is not working in the paper account",
+            ));
+        };
+        let mut record = state.working.remove(index).record;
+        record.status = OrderStatus::Cancelled;
+        record.cancellable = false;
+        record.editable = false;
+        self.emit(AccountMessage::Order(Box::new(record.clone())));
+        Ok(record)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, PaperState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn emit(&self, message: AccountMessage) {
+        _ = self.event_sender.send(message);
+    }
+
+    fn record(&self, order: &Order, id: OrderId, status: OrderStatus) -> LiveOrderRecord {
+        LiveOrderRecord {
+            id,
+            account_number: self.account_number.clone(),
+            time_in_force: order.time_in_force.clone(),
+            order_type: order.order_type.clone(),
+            size: order_size(order),
+            underlying_symbol: underlying_of(&order.legs[0].instrument_type, &order.legs[0].symbol),
+            price: order
+                .price
+                .zip(order.price_effect.as_ref())
+                .map(|(price, effect)| SignedAmount::new(price, effect)),
+            stop_trigger: order.stop_trigger,
+            status,
+            cancellable: true,
+            editable: true,
+            edited: false,
+            external_identifier: order.external_identifier.clone(),
+            received_at: Some(chrono::Utc::now()),
+            legs: order
+                .legs
+                .iter()
+                .map(|leg| LiveOrderLeg {
+                    instrument_type: leg.instrument_type.clone(),
+                    symbol: leg.symbol.clone(),
+                    quantity: leg.quantity.unwrap_or_default(),
+                    remaining_quantity: leg.quantity.unwrap_or_default(),
+                    action: leg.action.clone(),
+                    fills: vec![],
+                })
+                .collect(),
+        }
+    }
+
+    fn fees_for(&self, order: &Order) -> Decimal {
+        self.fees.per_order
+            + order
+                .legs
+                .iter()
+                .map(|leg| match leg.instrument_type {
+                    InstrumentType::Equity | InstrumentType::Cryptocurrency => {
+                        self.fees.per_share * leg.quantity.unwrap_or_default()
+                    }
+                    _ => self.fees.per_contract * leg.quantity.unwrap_or_default(),
+                })
+                .sum::<Decimal>()
+    }
+
+    /// Estimates buying power and fee effects from current quotes, falling back to the limit price.
+    fn preview(
+        &self,
+        state: &PaperState,
+        order: &Order,
+    ) -> (BuyingPowerEffect, FeeCalculation, Vec<Warning>) {
+        let mut warnings = vec![];
+        let value = match leg_prices(state, order) {
+            Some(prices) => cash_change(state, order, &prices),
+            None => {
+                warnings.push(Warning {
+                    code: "paper_no_quote".to_owned(),
+                    message: "No quote for every leg; estimate uses the order price".to_owned(),
+                    preflight_id: None,
+                });
+                let size = order_size(order);
+                let multiplier =
+                    multiplier(state, &order.legs[0].instrument_type, &order.legs[0].symbol);
+                order
+                    .price
+                    .zip(order.price_effect.as_ref())
+                    .map(|(price, effect)| SignedAmount::new(price * size * multiplier, effect))
+                    .unwrap_or_default()
+            }
+        };
+        let impact = if value.is_debit() {
+            value
+        } else {
+            SignedAmount::ZERO
+        };
+        let buying_power_effect = BuyingPowerEffect {
+            change_in_margin_requirement: SignedAmount::ZERO,
+            change_in_buying_power: impact,
+            current_buying_power: SignedAmount::credit(state.cash),
+            impact,
+        };
+        let fee_calculation = FeeCalculation {
+            total_fees: SignedAmount::debit(self.fees_for(order)),
+        };
+        (buying_power_effect, fee_calculation, warnings)
+    }
+
+    fn match_working(&self, state: &mut PaperState) {
+        let mut index = 0;
+        while index < state.working.len() {
+            if let Some(prices) = self.fill_prices(state, index) {
+                let working = state.working.remove(index);
+                self.fill(state, working, prices);
+            } else {
+                index += 1;
+            }
+        }
+    }
+
+    /// Returns per-leg fill prices if the working order at `index` is marketable.
+    fn fill_prices(&self, state: &mut PaperState, index: usize) -> Option<Vec<Decimal>> {
+        let working = &state.working[index];
+        let order = &working.order;
+        let prices = leg_prices(state, order)?;
+
+        let triggered = working.triggered
+            || match (&order.order_type, order.stop_trigger) {
+                (OrderType::Stop | OrderType::StopLimit, Some(trigger)) => {
+                    let leg = &order.legs[0];
+                    let quote = state.quotes.get(&leg.symbol)?;
+                    match leg.action.side()? {
+                        OrderSide::Buy => quote.ask >= trigger,
+                        OrderSide::Sell => quote.bid <= trigger,
+                    }
+                }
+                _ => true,
+            };
+        if !triggered {
+            return None;
+        }
+        state.working[index].triggered = true;
+
+        let order = &state.working[index].order;
+        let limit = match order.order_type {
+            OrderType::Limit | OrderType::StopLimit | OrderType::MarketableLimit => order
+                .price
+                .zip(order.price_effect.as_ref())
+                .map(|(price, effect)| SignedAmount::new(price, effect)),
+            _ => None,
+        };
+        if let Some(limit) = limit {
+            let size = order_size(order);
+            let net: SignedAmount = order
+                .legs
+                .iter()
+                .zip(&prices)
+                .map(|(leg, price)| {
+                    signed_value(
+                        leg.action.side(),
+                        *price * leg.quantity.unwrap_or_default() / size,
+                    )
+                })
+                .sum();
+            if net < limit {
+                return None;
+            }
+        }
+        Some(prices)
+    }
+
+    fn fill(&self, state: &mut PaperState, working: WorkingOrder, prices: Vec<Decimal>) {
+        let WorkingOrder {
+            order, mut record, ..
+        } = working;
+        let now = now();
+
+        let change = cash_change(state, &order, &prices).value() - self.fees_for(&order);
+        state.cash += change;
+
+        for ((leg, record_leg), price) in order.legs.iter().zip(&mut record.legs).zip(&prices) {
+            let delta = match leg.action.side() {
+                Some(OrderSide::Sell) => -leg.quantity.unwrap_or_default(),
+                _ => leg.quantity.unwrap_or_default(),
+            };
+            let multiplier = multiplier(state, &leg.instrument_type, &leg.symbol);
+            let position = state
+                .positions
+                .entry(leg.symbol.clone())
+                .or_insert_with(|| PaperPosition {
+                    instrument_type: leg.instrument_type.clone(),
+                    quantity: Decimal::ZERO,
+                    average_open_price: Decimal::ZERO,
+                    realized: Decimal::ZERO,
+                    created_at: now.clone(),
+                    updated_at: now.clone(),
+                });
+            position.apply(delta, *price, multiplier);
+            position.updated_at = now.clone();
+
+            record_leg.remaining_quantity = Decimal::ZERO;
+            record_leg.fills.push(Fill {
+                fill_id: state.next_fill_id.to_string(),
+                quantity: leg.quantity.unwrap_or_default(),
+                fill_price: *price,
+                filled_at: now.clone(),
+            });
+            state.next_fill_id += 1;
+
+            if let Some(position) = state.positions.get(&leg.symbol) {
+                self.emit(AccountMessage::CurrentPosition(Box::new(
+                    self.brief_position(state, &leg.symbol, position),
+                )));
+            }
+            if state.positions[&leg.symbol].quantity.is_zero() {
+                state.positions.remove(&leg.symbol);
+            }
+        }
+
+        record.status = OrderStatus::Filled;
+        record.cancellable = false;
+        record.editable = false;
+        self.emit(AccountMessage::Order(Box::new(record)));
+        self.emit(AccountMessage::AccountBalance(Box::new(
+            self.balance_of(state),
+        )));
+    }
+
+    fn balance_of(&self, state: &PaperState) -> Balance {
+        let mut long_equity = Decimal::ZERO;
+        let mut short_equity = Decimal::ZERO;
+        let mut long_derivative = Decimal::ZERO;
+        let mut short_derivative = Decimal::ZERO;
+        let mut long_futures = Decimal::ZERO;
+        let mut short_futures = Decimal::ZERO;
+        for (symbol, position) in &state.positions {
+            let value = position.quantity.abs()
+                * mark(state, symbol, position)
+                * multiplier(state, &position.instrument_type, symbol);
+            let (long, short) = match position.instrument_type {
+                InstrumentType::Equity | InstrumentType::Cryptocurrency => {
+                    (&mut long_equity, &mut short_equity)
+                }
+                InstrumentType::Future => (&mut long_futures, &mut short_futures),
+                _ => (&mut long_derivative, &mut short_derivative),
+            };
+            if position.quantity.is_sign_negative() {
+                *short += value;
+            } else {
+                *long += value;
+            }
+        }
+        let net_liquidating_value =
+            state.cash + long_equity - short_equity + long_derivative - short_derivative;
+        let cash = state.cash;
+        let zero = Decimal::ZERO;
+
+        Balance {
+            account_number: self.account_number.clone(),
+            cash_balance: cash,
+            long_equity_value: long_equity,
+            short_equity_value: short_equity,
+            long_derivative_value: long_derivative,
+            short_derivative_value: short_derivative,
+            long_futures_value: long_futures,
+            short_futures_value: short_futures,
+            long_futures_derivative_value: zero,
+            short_futures_derivative_value: zero,
+            long_margineable_value: zero,
+            short_margineable_value: zero,
+            margin_equity: net_liquidating_value,
+            equity_buying_power: cash,
+            derivative_buying_power: cash,
+            day_trading_buying_power: zero,
+            futures_margin_requirement: zero,
+            available_trading_funds: cash,
+            maintenance_requirement: zero,
+            maintenance_call_value: zero,
+            reg_t_call_value: zero,
+            day_trading_call_value: zero,
+            day_equity_call_value: zero,
+            net_liquidating_value,
+            cash_available_to_withdraw: cash,
+            day_trade_excess: zero,
+            pending_cash: SignedAmount::ZERO,
+            pending_margin_interest: zero,
+            effective_cryptocurrency_buying_power: cash,
+            updated_at: now(),
+        }
+    }
+
+    fn full_position(
+        &self,
+        state: &PaperState,
+        symbol: &Symbol,
+        position: &PaperPosition,
+    ) -> FullPosition {
+        let mark = mark(state, symbol, position);
+        let direction = position.direction();
+        let realized = SignedAmount::from(position.realized);
+        let today = chrono::Utc::now().date_naive().to_string();
+        FullPosition {
+            account_number: self.account_number.clone(),
+            symbol: symbol.clone(),
+            instrument_type: position.instrument_type.clone(),
+            underlying_symbol: underlying_of(&position.instrument_type, symbol),
+            quantity: position.quantity.abs(),
+            cost_effect: match direction {
+                QuantityDirection::Short => PriceEffect::Credit,
+                _ => PriceEffect::Debit,
+            },
+            quantity_direction: direction,
+            close_price: mark,
+            average_open_price: position.average_open_price,
+            average_yearly_market_close_price: mark,
+            average_daily_market_close_price: mark,
+            multiplier: multiplier(state, &position.instrument_type, symbol),
+            is_suppressed: false,
+            is_frozen: false,
+            restricted_quantity: Decimal::ZERO,
+            realized_day_gain: realized,
+            realized_day_gain_date: today.clone(),
+            realized_today: realized,
+            realized_today_date: today,
+            created_at: position.created_at.clone(),
+            updated_at: position.updated_at.clone(),
+        }
+    }
+
+    fn brief_position(
+        &self,
+        state: &PaperState,
+        symbol: &Symbol,
+        position: &PaperPosition,
+    ) -> BriefPosition {
+        let full = self.full_position(state, symbol, position);
+        BriefPosition {
+            account_number: full.account_number,
+            symbol: full.symbol,
+            instrument_type: full.instrument_type,
+            underlying_symbol: full.underlying_symbol,
+            quantity: full.quantity,
+            quantity_direction: full.quantity_direction,
+            close_price: full.close_price,
+            average_open_price: full.average_open_price,
+            multiplier: full.multiplier,
+            cost_effect: full.cost_effect,
+            is_suppressed: full.is_suppressed,
+            is_frozen: full.is_frozen,
+            restricted_quantity: full.restricted_quantity,
+            realized_day_gain: full.realized_day_gain,
+            realized_today: full.realized_today,
+            created_at: full.created_at,
+            updated_at: full.updated_at,
+        }
+    }
+}
+
+impl PaperPosition {
+    fn direction(&self) -> QuantityDirection {
+        if self.quantity.is_zero() {
+            QuantityDirection::Zero
+        } else if self.quantity.is_sign_negative() {
+            QuantityDirection::Short
+        } else {
+            QuantityDirection::Long
+        }
+    }
+
+    fn apply(&mut self, delta: Decimal, price: Decimal, multiplier: Decimal) {
+        let same_direction =
+            self.quantity.is_zero() || self.quantity.is_sign_negative() == delta.is_sign_negative();
+        if same_direction {
+            let total = self.quantity.abs() + delta.abs();
+            self.average_open_price =
+                (self.average_open_price * self.quantity.abs() + price * delta.abs()) / total;
+        } else {
+            let closed = delta.abs().min(self.quantity.abs());
+            let per_unit = if self.quantity.is_sign_negative() {
+                self.average_open_price - price
+            } else {
+                price - self.average_open_price
+            };
+            self.realized += closed * per_unit * multiplier;
+            if delta.abs() > self.quantity.abs() {
+                self.average_open_price = price;
+            }
+        }
+        self.quantity += delta;
+    }
+}
+
+fn api_error(code: &str, message: &str) -> crate::api::base::TastyError {
+    ApiError {
+        code: Some(code.to_owned()),
+        message: message.to_owned(),
+        errors: None,
+    }
+    .into()
+}
+
+fn now() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+/// The size of one unit of the order: the smallest leg quantity.
+fn order_size(order: &Order) -> Decimal {
+    order
+        .legs
+        .iter()
+        .filter_map(|leg| leg.quantity)
+        .min()
+        .unwrap_or(Decimal::ONE)
+}
+
+fn signed_value(side: Option<OrderSide>, value: Decimal) -> SignedAmount {
+    match side {
+        Some(OrderSide::Sell) => SignedAmount::credit(value),
+        _ => SignedAmount::debit(value),
+    }
+}
+
+fn leg_prices(state: &PaperState, order: &Order) -> Option<Vec<Decimal>> {
+    order
+        .legs
+        .iter()
+        .map(|leg| {
+            let quote = state.quotes.get(&leg.symbol)?;
+            Some(quote.price_for(leg.action.side()?))
+        })
+        .collect()
+}
+
+fn cash_change(state: &PaperState, order: &Order, prices: &[Decimal]) -> SignedAmount {
+    order
+        .legs
+        .iter()
+        .zip(prices)
+        .map(|(leg, price)| {
+            let multiplier = multiplier(state, &leg.instrument_type, &leg.symbol);
+            signed_value(
+                leg.action.side(),
+                *price * leg.quantity.unwrap_or_default() * multiplier,
+            )
+        })
+        .sum()
+}
+
+fn multiplier(state: &PaperState, instrument_type: &InstrumentType, symbol: &Symbol) -> Decimal {
+    state
+        .multipliers
+        .get(symbol)
+        .copied()
+        .unwrap_or(match instrument_type {
+            InstrumentType::EquityOption => Decimal::ONE_HUNDRED,
+            _ => Decimal::ONE,
+        })
+}
+
+fn mark(state: &PaperState, symbol: &Symbol, position: &PaperPosition) -> Decimal {
+    state
+        .quotes
+        .get(symbol)
+        .map(PaperQuote::mid)
+        .unwrap_or(position.average_open_price)
+}
+
+/// The underlying of an equity option is the root of its OCC symbol; anything else is its own underlying.
+fn underlying_of(instrument_type: &InstrumentType, symbol: &Symbol) -> Symbol {
+    match instrument_type {
+        InstrumentType::EquityOption => OptionSymbol::try_from(symbol)
+            .map(|option| Symbol(option.root))
+            .unwrap_or_else(|_| symbol.clone()),
+        _ => symbol.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::order::{Action, OrderLeg, TimeInForce};
+
+    const CALL: &str = "SPY   240119C00450000";
+
+    fn dec(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    fn order(
+        order_type: OrderType,
+        price: Option<&str>,
+        instrument_type: InstrumentType,
+        symbol: &str,
+        quantity: i64,
+        action: Action,
+    ) -> Order {
+        let price_effect = match action.side() {
+            Some(OrderSide::Sell) => PriceEffect::Credit,
+            _ => PriceEffect::Debit,
+        };
+        Order {
+            time_in_force: TimeInForce::Day,
+            order_type,
+            price: price.map(dec),
+            price_effect: Some(price_effect),
+            stop_trigger: None,
+            value: None,
+            value_effect: None,
+            external_identifier: None,
+            legs: vec![OrderLeg {
+                instrument_type,
+                symbol: symbol.into(),
+                quantity: Some(Decimal::from(quantity)),
+                action,
+            }],
+        }
+    }
+
+    fn market(symbol: &str, quantity: i64, action: Action) -> Order {
+        order(
+            OrderType::Market,
+            None,
+            InstrumentType::Equity,
+            symbol,
+            quantity,
+            action,
+        )
+    }
+
+    async fn position(account: &PaperAccount, symbol: &str) -> Option<FullPosition> {
+        account
+            .positions()
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|position| position.symbol.0 == symbol)
+    }
+
+    #[tokio::test]
+    async fn market_orders_fill_at_the_touch() {
+        let account = PaperAccount::new("5WT00000", Decimal::from(10_000)).with_fees(FeeSchedule {
+            per_order: Decimal::ONE,
+            per_share: dec("0.01"),
+            per_contract: Decimal::ZERO,
+        });
+        account.update_quote("SPY", dec("99.9"), dec("100.1"));
+
+        let placed = account
+            .place_order(&market("SPY", 10, Action::Buy))
+            .await
+            .unwrap();
+        assert_eq!(placed.order.status, OrderStatus::Filled);
+        assert_eq!(
+            placed.fee_calculation.total_fees,
+            SignedAmount::debit(dec("1.1"))
+        );
+        let balance = account.balance().await.unwrap();
+        assert_eq!(balance.cash_balance, dec("8997.9"));
+        assert_eq!(balance.long_equity_value, Decimal::from(1000));
+        assert_eq!(balance.net_liquidating_value, dec("9997.9"));
+
+        let spy = position(&account, "SPY").await.unwrap();
+        assert_eq!(spy.quantity, Decimal::from(10));
+        assert_eq!(spy.quantity_direction, QuantityDirection::Long);
+        assert_eq!(spy.average_open_price, dec("100.1"));
+
+        account.update_quote("SPY", dec("101.9"), dec("102.1"));
+        account
+            .place_order(&market("SPY", 4, Action::Sell))
+            .await
+            .unwrap();
+        let balance = account.balance().await.unwrap();
+        assert_eq!(balance.cash_balance, dec("9404.46"));
+        let spy = position(&account, "SPY").await.unwrap();
+        assert_eq!(spy.quantity, Decimal::from(6));
+        assert_eq!(spy.realized_today, SignedAmount::credit(dec("7.2")));
+
+        account
+            .place_order(&market("SPY", 6, Action::Sell))
+            .await
+            .unwrap();
+        assert!(position(&account, "SPY").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn limit_orders_wait_for_their_price() {
+        let account = PaperAccount::new("5WT00000", Decimal::from(1_000));
+        account.update_quote(CALL, dec("1.00"), dec("1.10"));
+        let buy = order(
+            OrderType::Limit,
+            Some("1.05"),
+            InstrumentType::EquityOption,
+            CALL,
+            1,
+            Action::BuyToOpen,
+        );
+
+        let placed = account.place_order(&buy).await.unwrap();
+        assert_eq!(placed.order.status, OrderStatus::Live);
+        assert_eq!(account.live_orders().await.unwrap().len(), 1);
+        assert_eq!(
+            account.balance().await.unwrap().cash_balance,
+            Decimal::from(1_000)
+        );
+
+        account.update_quote(CALL, dec("0.95"), dec("1.05"));
+        assert!(account.live_orders().await.unwrap().is_empty());
+        assert_eq!(
+            account.balance().await.unwrap().cash_balance,
+            Decimal::from(895)
+        );
+        let call = position(&account, CALL).await.unwrap();
+        assert_eq!(call.multiplier, Decimal::ONE_HUNDRED);
+        assert_eq!(call.underlying_symbol, Symbol::from("SPY"));
+    }
+
+    #[tokio::test]
+    async fn stop_orders_trigger_on_the_first_leg() {
+        let account = PaperAccount::new("5WT00000", Decimal::from(1_000));
+        account.update_quote("SPY", dec("100.0"), dec("100.2"));
+        let mut buy = order(
+            OrderType::Stop,
+            None,
+            InstrumentType::Equity,
+            "SPY",
+            1,
+            Action::Buy,
+        );
+        buy.stop_trigger = Some(Decimal::from(105));
+
+        account.place_order(&buy).await.unwrap();
+        assert_eq!(account.live_orders().await.unwrap().len(), 1);
+
+        account.update_quote("SPY", dec("104.9"), dec("105.0"));
+        assert!(account.live_orders().await.unwrap().is_empty());
+        assert_eq!(
+            account.balance().await.unwrap().cash_balance,
+            Decimal::from(895)
+        );
+    }
+
+    #[tokio::test]
+    async fn cancels_working_orders() {
+        let account = PaperAccount::new("5WT00000", Decimal::from(1_000));
+        let events = account.events();
+        let placed = account
+            .place_order(&market("SPY", 1, Action::Buy))
+            .await
+            .unwrap();
+
+        let cancelled = account.cancel_order(placed.order.id).await.unwrap();
+        assert_eq!(cancelled.status, OrderStatus::Cancelled);
+        assert!(account.live_orders().await.unwrap().is_empty());
+        assert!(account.cancel_order(placed.order.id).await.is_err());
+
+        let statuses: Vec<_> = events
+            .drain()
+            .filter_map(|message| match message {
+                AccountMessage::Order(order) => Some(order.status),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(statuses, [OrderStatus::Live, OrderStatus::Cancelled]);
+    }
+
+    #[tokio::test]
+    async fn rejects_orders_beyond_cash() {
+        let account = PaperAccount::new("5WT00000", Decimal::from(100));
+        account.update_quote("SPY", dec("99.9"), dec("100.1"));
+        assert!(account
+            .place_order(&market("SPY", 1, Action::Buy))
+            .await
+            .is_err());
+        assert!(account.live_orders().await.unwrap().is_empty());
+        assert_eq!(
+            account.balance().await.unwrap().cash_balance,
+            Decimal::from(100)
+        );
+    }
+}
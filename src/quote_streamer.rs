@@ -30,7 +30,9 @@ pub struct SubscriptionId(usize);
 ///
 /// The tastytrade API uses the OCC symbol format for options, e.g. "SPXW  230623P04295000"
 /// DxFeed uses varying symbols depending on the data provider. To convert
-/// tastytrade symbols to DxFeed ones, use [`crate::TastyTrade::get_streamer_symbol`]
+/// tastytrade symbols to DxFeed ones, use [`crate::TastyTrade::get_streamer_symbol`].
+/// Equity options can also be converted locally with
+/// [`crate::api::symbology::OptionSymbol::to_streamer_symbol`].
 #[derive(Debug)]
 pub struct QuoteSubscription {
     pub id: SubscriptionId,
@@ -0,0 +1,176 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures_util::future::join_all;
+use tokio::sync::{broadcast, watch};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use crate::accounts::{Account, OwnedAccount};
+use crate::api::account_streaming::{AccountStreamer, ConnectionState};
+use crate::api::order::{LiveOrderRecord, OrderId, OrderStatus};
+use crate::Result;
+
+const CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone)]
+pub struct WatchdogConfig {
+    /// How long the account streamer may stay disconnected before orders are cancelled.
+    pub disconnect_grace: Duration,
+    /// How long the application may go without calling [`OrderWatchdog::ping`].
+    /// `None` disables the keep-alive check.
+    pub ping_timeout: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TripReason {
+    Disconnected,
+    PingTimeout,
+}
+
+/// Sent on [`OrderWatchdog::trips`] when the watchdog fires.
+#[derive(Debug)]
+pub struct WatchdogTrip {
+    pub reason: TripReason,
+    pub cancelled: Vec<(OrderId, Result<LiveOrderRecord>)>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    orders: Mutex<HashSet<OrderId>>,
+    last_ping: Mutex<Instant>,
+}
+
+/// A dead man's switch that cancels tracked orders when the application goes quiet.
+///
+/// Orders placed through an account with [`Account::with_watchdog`] are tracked
+/// until cancelled through it or, with a streamer, until it reports them done.
+/// The watchdog fires once, cancelling every tracked order, when the account
+/// streamer stays disconnected longer than the grace period or when pings stop
+/// arriving; it then stops. Cancellations go through [`Account::cancel_order`],
+/// so they are journaled if the account had a journal when the watchdog was
+/// started. Dropping the watchdog disarms it.
+#[derive(Debug)]
+pub struct OrderWatchdog {
+    shared: Arc<Shared>,
+    trips: flume::Receiver<WatchdogTrip>,
+    task: JoinHandle<()>,
+}
+
+impl OrderWatchdog {
+    pub fn start(
+        account: &Account<'_>,
+        streamer: Option<&AccountStreamer>,
+        config: WatchdogConfig,
+    ) -> Arc<Self> {
+        let shared = Arc::new(Shared {
+            orders: Mutex::new(HashSet::new()),
+            last_ping: Mutex::new(Instant::now()),
+        });
+        let (trip_sender, trips) = flume::unbounded();
+        let task = tokio::spawn(watch_loop(
+            account.to_owned_account(),
+            streamer.map(AccountStreamer::connection_state),
+            streamer.map(AccountStreamer::order_updates),
+            config,
+            shared.clone(),
+            trip_sender,
+        ));
+        Arc::new(Self {
+            shared,
+            trips,
+            task,
+        })
+    }
+
+    /// Tells the watchdog the application is still alive.
+    pub fn ping(&self) {
+        *lock(&self.shared.last_ping) = Instant::now();
+    }
+
+    pub fn track(&self, id: OrderId) {
+        lock(&self.shared.orders).insert(id);
+    }
+
+    /// Stops tracking an order, e.g. once it has been filled.
+    pub fn untrack(&self, id: OrderId) {
+        lock(&self.shared.orders).remove(&id);
+    }
+
+    pub fn tracked(&self) -> Vec<OrderId> {
+        lock(&self.shared.orders).iter().copied().collect()
+    }
+
+    pub fn trips(&self) -> flume::Receiver<WatchdogTrip> {
+        self.trips.clone()
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.task.is_finished()
+    }
+}
+
+impl Drop for OrderWatchdog {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn watch_loop(
+    account: OwnedAccount,
+    connection: Option<watch::Receiver<ConnectionState>>,
+    mut order_updates: Option<broadcast::Receiver<(OrderId, OrderStatus)>>,
+    config: WatchdogConfig,
+    shared: Arc<Shared>,
+    trips: flume::Sender<WatchdogTrip>,
+) {
+    let mut disconnected_since = None;
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+    let reason = loop {
+        interval.tick().await;
+        let now = Instant::now();
+
+        if let Some(updates) = &mut order_updates {
+            loop {
+                match updates.try_recv() {
+                    Ok((id, status)) if status.is_terminal() => {
+                        lock(&shared.orders).remove(&id);
+                    }
+                    Ok(_) | Err(broadcast::error::TryRecvError::Lagged(_)) => {}
+                    Err(_) => break,
+                }
+            }
+        }
+
+        let connected = connection
+            .as_ref()
+            .is_none_or(|state| *state.borrow() == ConnectionState::Connected);
+        if connected {
+            disconnected_since = None;
+        } else {
+            let since = *disconnected_since.get_or_insert(now);
+            if now - since >= config.disconnect_grace {
+                break TripReason::Disconnected;
+            }
+        }
+
+        if let Some(timeout) = config.ping_timeout {
+            if now - *lock(&shared.last_ping) >= timeout {
+                break TripReason::PingTimeout;
+            }
+        }
+    };
+
+    let ids: Vec<OrderId> = lock(&shared.orders).drain().collect();
+    let account = account.account();
+    let results = join_all(ids.iter().map(|id| account.cancel_order(*id))).await;
+    _ = trips.send(WatchdogTrip {
+        reason,
+        cancelled: ids.into_iter().zip(results).collect(),
+    });
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}